@@ -19,6 +19,10 @@ impl Config {
                 username: None,
                 password: None,
                 parallel: 3,
+                retry: Some(RetryPolicy {
+                    max_retries: 2,
+                    backoff_secs: 5,
+                }),
             }),
             ui_config: Some(UiConfig {
                 theme: Theme::Light as i32,
@@ -29,6 +33,21 @@ impl Config {
                 foreground_opacity: 0.9,
             }),
             active_profile: None,
+            steam_api_key: None,
+            workshop_cache: Some(WorkshopCacheConfig {
+                ttl_secs: 6 * 60 * 60,
+                stale_while_revalidate: true,
+                offline: false,
+            }),
+            workshop_requests: Some(WorkshopRequestConfig {
+                concurrency: 4,
+                timeout_secs: 15,
+                retry: Some(RetryPolicy {
+                    max_retries: 3,
+                    backoff_secs: 1,
+                }),
+            }),
+            preview_cache_max_mb: 256,
         }
     }
 
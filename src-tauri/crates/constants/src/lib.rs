@@ -6,6 +6,8 @@ pub const MOD_FILELIST_FILE: &str = "filelist.xml";
 
 pub const LOG_DIR_NAME: &str = "log";
 
+pub const STEAMCMD_DIR_NAME: &str = "steamcmd";
+
 pub const BAROTRAUMA_GAME_ID: u64 = 602960;
 
 /// The application name used across the project.
@@ -41,6 +43,17 @@ pub static GLOBAL_CONFIG_FILE: LazyLock<PathBuf> = LazyLock::new(|| ROAMING.join
 /// A global static instance of the user's global log directory for the application.
 pub static GLOBAL_LOG_DIR: LazyLock<PathBuf> = LazyLock::new(|| ROAMING.join(LOG_DIR_NAME));
 
+/// A global static instance of the directory where the application installs its own SteamCMD.
+pub static MANAGED_STEAMCMD_HOME: LazyLock<PathBuf> =
+    LazyLock::new(|| ROAMING.join(STEAMCMD_DIR_NAME));
+
+/// A global static instance of the on-disk cache of Steam Workshop metadata.
+pub static WORKSHOP_METADATA_CACHE_FILE: LazyLock<PathBuf> =
+    LazyLock::new(|| ROAMING.join("workshop_metadata.json"));
+
+/// A global static instance of the directory holding cached preview thumbnails.
+pub static PREVIEW_CACHE_DIR: LazyLock<PathBuf> = LazyLock::new(|| ROAMING.join("previews"));
+
 /// Represents the Barotrauma game home directory.
 #[derive(Debug)]
 pub struct BarotraumaHome {
@@ -1,5 +1,6 @@
 use logger::info;
 use std::path::Path;
+use walkdir::WalkDir;
 
 pub mod hash;
 
@@ -26,4 +27,16 @@ pub fn link_directory(src: String, dst: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Returns the total size in bytes of all files below `path`.
+pub fn directory_size<P: AsRef<Path>>(path: P) -> u64 {
+    WalkDir::new(path)
+        .min_depth(1)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
 pub use hash::*;
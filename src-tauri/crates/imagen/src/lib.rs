@@ -7,6 +7,9 @@ use libblur::{
 use rayon::prelude::*;
 use std::path::PathBuf;
 
+mod preview;
+pub use preview::{PreviewCache, PreviewError, THUMBNAIL_SIZES, thumbnail_data_url};
+
 /// Configuration: background image path, blur radius, opacity
 #[derive(Debug, Clone)]
 pub struct BackgroundConfig {
@@ -0,0 +1,310 @@
+use base64::{Engine as _, engine::general_purpose};
+use image::ImageFormat;
+use logger::{debug, info};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+static STAGING_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Edge lengths in pixels of the thumbnails generated for every preview.
+pub const THUMBNAIL_SIZES: [u32; 3] = [64, 256, 512];
+
+/// On-disk cache of Workshop preview thumbnails.
+///
+/// Every preview is decoded once and stored as lossless WebP thumbnails, one per entry of
+/// [`THUMBNAIL_SIZES`], under `<dir>/<key>/<size>.webp`. The key is the preview's content
+/// hash (`hcontent_preview`), so a changed preview gets a new entry. When the cache grows
+/// past its size limit the least recently used previews are evicted.
+#[derive(Debug)]
+pub struct PreviewCache {
+    dir: PathBuf,
+    max_bytes: AtomicU64,
+}
+
+/// One cached preview, as seen by [`PreviewCache::evict`].
+struct Entry {
+    dir: PathBuf,
+    bytes: u64,
+    last_used: SystemTime,
+}
+
+impl Entry {
+    fn read(dir: PathBuf) -> io::Result<Entry> {
+        let mut entry = Entry {
+            dir,
+            bytes: 0,
+            last_used: SystemTime::UNIX_EPOCH,
+        };
+        for file in fs::read_dir(&entry.dir)? {
+            let metadata = file?.metadata()?;
+            entry.bytes += metadata.len();
+            entry.last_used = entry.last_used.max(metadata.modified()?);
+        }
+        Ok(entry)
+    }
+}
+
+/// Removes the preview stored in `dir`, which a concurrent insert may have removed
+/// already.
+fn remove_entry(dir: &Path) -> io::Result<()> {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+impl PreviewCache {
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        PreviewCache {
+            dir,
+            max_bytes: AtomicU64::new(max_bytes),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Changes the size limit, it applies from the next insert on.
+    pub fn set_max_bytes(&self, max_bytes: u64) {
+        self.max_bytes.store(max_bytes, Ordering::Relaxed);
+    }
+
+    fn entry_dir(&self, key: &str) -> Result<PathBuf, PreviewError> {
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(PreviewError::InvalidKey(key.to_string()));
+        }
+        Ok(self.dir.join(key))
+    }
+
+    /// The cached thumbnail of `key` that best fits `size`: the smallest one at least
+    /// `size` pixels large, or the largest one.
+    ///
+    /// Marks the preview as used, so it is evicted last.
+    pub fn get(&self, key: &str, size: u32) -> Option<PathBuf> {
+        let dir = self.entry_dir(key).ok()?;
+        let fitting = THUMBNAIL_SIZES
+            .iter()
+            .position(|&thumbnail| thumbnail >= size)
+            .unwrap_or(THUMBNAIL_SIZES.len() - 1);
+        let path = dir.join(format!("{}.webp", THUMBNAIL_SIZES[fitting]));
+        let file = File::options().append(true).open(&path).ok()?;
+        if let Err(e) = file.set_modified(SystemTime::now()) {
+            debug!("Failed to touch {:?}: {}", path, e);
+        }
+        Some(path)
+    }
+
+    /// Decodes the preview `image` and stores its thumbnails under `key`, then evicts
+    /// old previews if the cache is over its size limit.
+    ///
+    /// The new preview itself is never evicted, even when it alone exceeds the limit.
+    /// Previews smaller than a thumbnail size are stored at their own size.
+    pub fn insert(&self, key: &str, image: &[u8]) -> Result<(), PreviewError> {
+        let dir = self.entry_dir(key)?;
+        let image = image::load_from_memory(image)?;
+
+        // Unique per insert, previews of the same key may be inserted concurrently
+        let staging = self.dir.join(format!(
+            ".{key}.{}.tmp",
+            STAGING_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&staging)?;
+        for size in THUMBNAIL_SIZES {
+            let thumbnail = if image.width() > size || image.height() > size {
+                image.thumbnail(size, size)
+            } else {
+                image.clone()
+            };
+            thumbnail
+                .to_rgba8()
+                .save_with_format(staging.join(format!("{size}.webp")), ImageFormat::WebP)?;
+        }
+        remove_entry(&dir)?;
+        if let Err(e) = fs::rename(&staging, &dir) {
+            fs::remove_dir_all(&staging)?;
+            // Lost the race against another insert of the same preview
+            if !dir.exists() {
+                return Err(e.into());
+            }
+        }
+
+        self.evict_except(Some(&dir))?;
+        Ok(())
+    }
+
+    /// Total size of the cached thumbnails in bytes.
+    pub fn size_bytes(&self) -> Result<u64, PreviewError> {
+        Ok(self.entries()?.iter().map(|entry| entry.bytes).sum())
+    }
+
+    /// Every cached preview. Previews that disappear while the cache is read, because
+    /// a concurrent insert replaced or evicted them, are left out.
+    fn entries(&self) -> Result<Vec<Entry>, PreviewError> {
+        let dirs = match fs::read_dir(&self.dir) {
+            Ok(dirs) => dirs,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let mut entries = Vec::new();
+        for dir in dirs {
+            let dir = dir?;
+            if !dir.file_type()?.is_dir() || dir.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            match Entry::read(dir.path()) {
+                Ok(entry) => entries.push(entry),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Removes the least recently used previews until the cache fits its size limit.
+    ///
+    /// Returns the number of bytes freed.
+    pub fn evict(&self) -> Result<u64, PreviewError> {
+        self.evict_except(None)
+    }
+
+    /// [`PreviewCache::evict`], keeping the preview stored in `keep`.
+    fn evict_except(&self, keep: Option<&Path>) -> Result<u64, PreviewError> {
+        let mut entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|entry| entry.bytes).sum();
+        let max_bytes = self.max_bytes();
+        if total <= max_bytes {
+            return Ok(0);
+        }
+
+        entries.sort_by_key(|entry| entry.last_used);
+        let mut freed = 0;
+        for entry in entries {
+            if total <= max_bytes {
+                break;
+            }
+            if keep == Some(entry.dir.as_path()) {
+                continue;
+            }
+            remove_entry(&entry.dir)?;
+            total -= entry.bytes;
+            freed += entry.bytes;
+        }
+        info!("Evicted {} bytes of preview thumbnails", freed);
+        Ok(freed)
+    }
+
+    /// Removes every cached preview.
+    pub fn clear(&self) -> Result<(), PreviewError> {
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads a thumbnail returned by [`PreviewCache::get`] as a `data:image/webp;base64,...` URL.
+pub fn thumbnail_data_url(path: &Path) -> Result<String, PreviewError> {
+    let data = fs::read(path)?;
+    Ok(format!(
+        "data:image/webp;base64,{}",
+        general_purpose::STANDARD.encode(data)
+    ))
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PreviewError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Image error: {0}")]
+    Image(#[from] image::ImageError),
+
+    #[error("invalid preview key {0:?}")]
+    InvalidKey(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, RgbImage};
+    use std::io::Cursor;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(width, height))
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_insert_generates_thumbnails() {
+        let dir = TempDir::new().unwrap();
+        let cache = PreviewCache::new(dir.path().to_path_buf(), u64::MAX);
+        cache.insert("abc123", &png(1024, 512)).unwrap();
+
+        let path = cache.get("abc123", 200).unwrap();
+        assert!(path.ends_with("abc123/256.webp"));
+        let thumbnail = image::open(&path).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (256, 128));
+        assert!(cache.get("abc123", 4096).unwrap().ends_with("512.webp"));
+        assert!(cache.get("missing", 64).is_none());
+        assert!(matches!(
+            cache.insert("../escape", &png(8, 8)),
+            Err(PreviewError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let dir = TempDir::new().unwrap();
+        let cache = PreviewCache::new(dir.path().to_path_buf(), u64::MAX);
+        cache.insert("old", &png(600, 600)).unwrap();
+        cache.insert("new", &png(600, 600)).unwrap();
+        // Mark "old" as used well before "new".
+        for size in THUMBNAIL_SIZES {
+            File::options()
+                .append(true)
+                .open(dir.path().join(format!("old/{size}.webp")))
+                .unwrap()
+                .set_modified(SystemTime::now() - Duration::from_secs(3600))
+                .unwrap();
+        }
+
+        let one = cache.size_bytes().unwrap() / 2;
+        cache.set_max_bytes(one + 1);
+        assert!(cache.evict().unwrap() > 0);
+        assert!(cache.get("old", 64).is_none());
+        assert!(cache.get("new", 64).is_some());
+    }
+
+    #[test]
+    fn test_insert_keeps_entry_larger_than_limit() {
+        let dir = TempDir::new().unwrap();
+        let cache = PreviewCache::new(dir.path().to_path_buf(), 0);
+        cache.insert("first", &png(600, 600)).unwrap();
+        assert!(cache.get("first", 64).is_some());
+
+        cache.insert("second", &png(600, 600)).unwrap();
+        assert!(cache.get("first", 64).is_none());
+        assert!(cache.get("second", 64).is_some());
+    }
+
+    #[test]
+    fn test_vanished_entries_are_not_errors() {
+        let dir = TempDir::new().unwrap();
+        let cache = PreviewCache::new(dir.path().join("previews"), 0);
+        assert_eq!(cache.size_bytes().unwrap(), 0);
+        assert!(remove_entry(&cache.dir().join("gone")).is_ok());
+    }
+}
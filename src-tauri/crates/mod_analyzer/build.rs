@@ -10,6 +10,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "mods.BarotraumaMod",
                 "#[derive(serde::Serialize, serde::Deserialize)]\n#[serde(rename_all = \"camelCase\", default)]",
             ),
+            (
+                "mods.CreatorProfile",
+                "#[derive(serde::Serialize, serde::Deserialize)]\n#[serde(rename_all = \"camelCase\", default)]",
+            ),
         ],
         &[
             ("mods.BarotraumaMod.name", "#[serde(alias = \"@name\")]"),
@@ -33,6 +37,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "mods.BarotraumaMod.expectedHash",
                 "#[serde(alias = \"@expectedhash\", alias = \"expectedHash\")]",
             ),
+            (
+                "mods.BarotraumaMod.lastModified",
+                "#[serde(alias = \"@installtime\", alias = \"lastModified\", deserialize_with=\"crate::deserialize_optional_u64\")]",
+            ),
         ],
     )
 }
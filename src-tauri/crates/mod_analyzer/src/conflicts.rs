@@ -0,0 +1,345 @@
+use crate::content::ContentFile;
+use crate::mods::BarotraumaMod;
+use logger::debug;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Content types whose files define prefabs by identifier, and are checked for
+/// collisions by [`detect_content_conflicts`].
+pub const IDENTIFIED_CONTENT_TYPES: [&str; 16] = [
+    "Item",
+    "Character",
+    "Afflictions",
+    "Structure",
+    "Jobs",
+    "Talents",
+    "TalentTrees",
+    "Factions",
+    "Missions",
+    "Orders",
+    "Corpses",
+    "ItemAssembly",
+    "NPCSets",
+    "LocationTypes",
+    "EventManagerSettings",
+    "RandomEvents",
+];
+
+/// A prefab defined by a content file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefabDefinition {
+    /// The identifier, lowercased as the game compares identifiers case-insensitively.
+    pub identifier: String,
+    /// Whether the prefab is wrapped in an `<Override>` element.
+    pub is_override: bool,
+}
+
+/// Parses the prefabs defined by a content file.
+///
+/// A prefab is the root element, or a child of the root container such as `<Items>`,
+/// carrying an `identifier` attribute (`speciesname` for characters). `<Override>`
+/// wrappers are looked through, at the root as well as inside the container.
+pub fn parse_prefab_definitions(
+    xml: &str,
+) -> Result<Vec<PrefabDefinition>, Box<dyn std::error::Error>> {
+    // One frame per open element: whether its children may be prefabs, and whether
+    // it is inside an `<Override>`.
+    struct Frame {
+        container: bool,
+        is_override: bool,
+    }
+
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    let mut definitions = Vec::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        let (element, empty) = match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => (e, false),
+            Ok(Event::Empty(e)) => (e, true),
+            Ok(Event::End(_)) => {
+                stack.pop();
+                buf.clear();
+                continue;
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(Box::new(e)),
+            _ => {
+                buf.clear();
+                continue;
+            }
+        };
+
+        let (candidate, parent_override) = stack.last().map_or((true, false), |parent| {
+            (parent.container, parent.is_override)
+        });
+        let frame = if !candidate {
+            Frame {
+                container: false,
+                is_override: parent_override,
+            }
+        } else if element.name().as_ref().eq_ignore_ascii_case(b"Override") {
+            Frame {
+                container: true,
+                is_override: true,
+            }
+        } else if let Some(identifier) = identifier(&element, &reader)? {
+            definitions.push(PrefabDefinition {
+                identifier,
+                is_override: parent_override,
+            });
+            Frame {
+                container: false,
+                is_override: parent_override,
+            }
+        } else {
+            // A container like `<Items>`, at the root or inside an `<Override>`
+            Frame {
+                container: stack.len() <= 1 || parent_override,
+                is_override: parent_override,
+            }
+        };
+        if !empty {
+            stack.push(frame);
+        }
+        buf.clear();
+    }
+    Ok(definitions)
+}
+
+fn identifier(
+    element: &BytesStart,
+    reader: &Reader<&[u8]>,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    for attr in element.attributes().flatten() {
+        let key = attr.key.as_ref();
+        if key.eq_ignore_ascii_case(b"identifier") || key.eq_ignore_ascii_case(b"speciesname") {
+            let value = attr
+                .decoded_and_normalized_value(XmlVersion::Implicit1_0, reader.decoder())?
+                .trim()
+                .to_lowercase();
+            if !value.is_empty() {
+                return Ok(Some(value));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// One mod's definition of a conflicting prefab.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictingDefinition {
+    pub mod_name: String,
+    pub mod_steam_id: u64,
+    /// The content file defining the prefab.
+    pub file: PathBuf,
+    pub is_override: bool,
+}
+
+/// How the game resolves a [`ContentConflict`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictKind {
+    /// At most one mod defines the prefab without an `<Override>`, the game replaces it
+    /// as intended.
+    Override,
+    /// Several mods define the prefab without overriding it, the game reports a
+    /// duplicate identifier and may crash.
+    Duplicate,
+}
+
+/// A prefab identifier defined by more than one enabled mod.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentConflict {
+    /// The content type of the defining files, such as `Item` or `Character`.
+    pub content_type: String,
+    pub identifier: String,
+    pub kind: ConflictKind,
+    /// The definitions in load order, the first one is used by the game.
+    pub definitions: Vec<ConflictingDefinition>,
+}
+
+impl ContentConflict {
+    /// The definition the game uses.
+    pub fn winner(&self) -> &ConflictingDefinition {
+        &self.definitions[0]
+    }
+}
+
+/// Finds prefab identifiers defined by more than one of `mods`.
+///
+/// `mods` are the enabled mods with their [`ContentFile`]s in `<regularpackages>` order,
+/// where mods higher in the list take precedence. An `<Override>` definition wins over
+/// plain ones, ties go to the mod higher in the list. Missing and malformed files are
+/// skipped. Conflicts are sorted by content type and identifier.
+pub fn detect_content_conflicts(
+    mods: &[(BarotraumaMod, Vec<ContentFile>)],
+) -> Vec<ContentConflict> {
+    let parsed: Vec<(usize, &ContentFile, Vec<PrefabDefinition>)> = mods
+        .iter()
+        .enumerate()
+        .flat_map(|(index, (_, files))| files.iter().map(move |file| (index, file)))
+        .filter(|(_, file)| {
+            file.exists && IDENTIFIED_CONTENT_TYPES.contains(&file.element_type.as_str())
+        })
+        .par_bridge()
+        .filter_map(|(index, file)| {
+            let path = file.path.as_ref()?;
+            let definitions = fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|xml| parse_prefab_definitions(&xml).map_err(|e| e.to_string()));
+            match definitions {
+                Ok(definitions) => Some((index, file, definitions)),
+                Err(e) => {
+                    debug!("Skipping content file {:?}: {}", path, e);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    let mut by_identifier: BTreeMap<(&str, &str), Vec<(usize, ConflictingDefinition)>> =
+        BTreeMap::new();
+    for (index, file, definitions) in &parsed {
+        let (mod_obj, _) = &mods[*index];
+        for definition in definitions {
+            by_identifier
+                .entry((file.element_type.as_str(), definition.identifier.as_str()))
+                .or_default()
+                .push((
+                    *index,
+                    ConflictingDefinition {
+                        mod_name: mod_obj.name.clone(),
+                        mod_steam_id: mod_obj.steam_workshop_id,
+                        file: file.path.clone().unwrap_or_default(),
+                        is_override: definition.is_override,
+                    },
+                ));
+        }
+    }
+
+    by_identifier
+        .into_iter()
+        .filter(|(_, definitions)| {
+            definitions
+                .iter()
+                .any(|(index, _)| *index != definitions[0].0)
+        })
+        .map(|((content_type, identifier), mut definitions)| {
+            definitions.sort_by_key(|(index, definition)| (!definition.is_override, *index));
+            let definitions: Vec<ConflictingDefinition> = definitions
+                .into_iter()
+                .map(|(_, definition)| definition)
+                .collect();
+            let kind = if definitions.iter().filter(|d| !d.is_override).count() > 1 {
+                ConflictKind::Duplicate
+            } else {
+                ConflictKind::Override
+            };
+            ContentConflict {
+                content_type: content_type.to_string(),
+                identifier: identifier.to_string(),
+                kind,
+                definitions,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::parse_content_files;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_prefab_definitions() {
+        let xml = r#"<Items>
+  <Item identifier="Crowbar"><Sprite identifier="not a prefab" /></Item>
+  <Override>
+    <Item identifier="wrench" />
+  </Override>
+</Items>"#;
+        let definitions = parse_prefab_definitions(xml).unwrap();
+        assert_eq!(
+            definitions,
+            vec![
+                PrefabDefinition {
+                    identifier: "crowbar".to_string(),
+                    is_override: false
+                },
+                PrefabDefinition {
+                    identifier: "wrench".to_string(),
+                    is_override: true
+                },
+            ]
+        );
+
+        let character =
+            r#"<Override><Character speciesname="Crawler"><ragdoll /></Character></Override>"#;
+        assert_eq!(
+            parse_prefab_definitions(character).unwrap(),
+            vec![PrefabDefinition {
+                identifier: "crawler".to_string(),
+                is_override: true
+            }]
+        );
+    }
+
+    fn install(game: &Path, id: u64, items: &str) -> (BarotraumaMod, Vec<ContentFile>) {
+        let dir = game.join("LocalMods").join(id.to_string());
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("items.xml"), items).unwrap();
+        let xml = format!(
+            r#"<contentpackage name="Mod {id}" steamworkshopid="{id}"><Item file="%ModDir%/items.xml" /></contentpackage>"#
+        );
+        let mod_obj = BarotraumaMod::from_xml_string(&xml).unwrap();
+        let files = parse_content_files(&xml, &dir, game, |_| None).unwrap();
+        (mod_obj, files)
+    }
+
+    #[test]
+    fn test_detect_content_conflicts() {
+        let game = TempDir::new().unwrap();
+        let mods = vec![
+            install(
+                game.path(),
+                1,
+                r#"<Items><Item identifier="a" /><Item identifier="b" /></Items>"#,
+            ),
+            install(
+                game.path(),
+                2,
+                r#"<Items><Item identifier="A" /><Item identifier="c" /></Items>"#,
+            ),
+            install(
+                game.path(),
+                3,
+                r#"<Override><Item identifier="b" /></Override>"#,
+            ),
+        ];
+
+        let conflicts = detect_content_conflicts(&mods);
+        assert_eq!(conflicts.len(), 2);
+
+        assert_eq!(conflicts[0].identifier, "a");
+        assert_eq!(conflicts[0].kind, ConflictKind::Duplicate);
+        assert_eq!(conflicts[0].winner().mod_steam_id, 1);
+
+        assert_eq!(conflicts[1].identifier, "b");
+        assert_eq!(conflicts[1].kind, ConflictKind::Override);
+        assert_eq!(conflicts[1].winner().mod_steam_id, 3);
+        assert_eq!(conflicts[1].definitions[1].mod_steam_id, 1);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::mods::BarotraumaMod;
+use constants::MOD_FILELIST_FILE;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A file referenced by a child element of `<contentpackage>`, such as
+/// `<Item file="%ModDir%/Items/items.xml" />`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentFile {
+    /// The element name, which is the content type: `Item`, `Character`, `Submarine`...
+    pub element_type: String,
+    /// The `file` attribute as written in filelist.xml.
+    pub file: String,
+    /// `file` with `%ModDir%` and `%ModDir:<name>%` expanded, `None` when it refers to
+    /// a mod that is not installed.
+    pub path: Option<PathBuf>,
+    /// Whether `path` exists on disk.
+    pub exists: bool,
+}
+
+/// Parses the content files a Barotrauma filelist.xml string references.
+///
+/// Paths are resolved the way the game does:
+/// - `%ModDir%/...` is relative to `mod_dir`, the directory of the filelist.xml.
+/// - `%ModDir:<name>%/...` is relative to the directory of another mod, given by its
+///   name or Steam Workshop ID and looked up with `other_mod_dir`.
+/// - Anything else is relative to `game_dir`, like vanilla `Content/...` paths.
+///
+/// Both `/` and `\` are accepted as separators.
+pub fn parse_content_files(
+    xml: &str,
+    mod_dir: &Path,
+    game_dir: &Path,
+    other_mod_dir: impl Fn(&str) -> Option<PathBuf>,
+) -> Result<Vec<ContentFile>, Box<dyn std::error::Error>> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+    let mut files = Vec::new();
+    let mut buf = Vec::new();
+    let mut depth = 0usize;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                if depth == 1 {
+                    files.extend(content_file(e, &reader, mod_dir, game_dir, &other_mod_dir)?);
+                }
+                depth += 1;
+            }
+            Ok(Event::Empty(ref e)) if depth == 1 => {
+                files.extend(content_file(e, &reader, mod_dir, game_dir, &other_mod_dir)?);
+            }
+            Ok(Event::End(_)) => depth = depth.saturating_sub(1),
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(Box::new(e)),
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(files)
+}
+
+fn content_file(
+    element: &BytesStart,
+    reader: &Reader<&[u8]>,
+    mod_dir: &Path,
+    game_dir: &Path,
+    other_mod_dir: &impl Fn(&str) -> Option<PathBuf>,
+) -> Result<Option<ContentFile>, Box<dyn std::error::Error>> {
+    for attr in element.attributes().flatten() {
+        if !attr.key.as_ref().eq_ignore_ascii_case(b"file") {
+            continue;
+        }
+        let file = attr
+            .decoded_and_normalized_value(XmlVersion::Implicit1_0, reader.decoder())?
+            .into_owned();
+        if file.is_empty() {
+            return Ok(None);
+        }
+        let path = resolve_path(&file, mod_dir, game_dir, other_mod_dir);
+        return Ok(Some(ContentFile {
+            element_type: String::from_utf8_lossy(element.name().as_ref()).into_owned(),
+            exists: path.as_ref().is_some_and(|path| path.exists()),
+            file,
+            path,
+        }));
+    }
+    Ok(None)
+}
+
+/// Expands the `%ModDir%` or `%ModDir:<name>%` prefix of `file`.
+fn resolve_path(
+    file: &str,
+    mod_dir: &Path,
+    game_dir: &Path,
+    other_mod_dir: &impl Fn(&str) -> Option<PathBuf>,
+) -> Option<PathBuf> {
+    let (base, rest) = match file.strip_prefix('%').and_then(|tail| tail.split_once('%')) {
+        Some((variable, rest)) if variable.eq_ignore_ascii_case("ModDir") => {
+            (mod_dir.to_path_buf(), rest)
+        }
+        Some((variable, rest))
+            if variable
+                .get(..7)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case("ModDir:")) =>
+        {
+            (other_mod_dir(&variable[7..])?, rest)
+        }
+        _ => (game_dir.to_path_buf(), file),
+    };
+    Some(
+        rest.split(['/', '\\'])
+            .filter(|part| !part.is_empty())
+            .fold(base, |path, part| path.join(part)),
+    )
+}
+
+impl BarotraumaMod {
+    /// The content files referenced by the mod's filelist.xml, see [`parse_content_files`].
+    ///
+    /// # Arguments
+    /// * `game_dir` - The Barotrauma install directory.
+    /// * `other_mod_dir` - Looks up the directory of another mod by name or Steam Workshop ID.
+    pub fn content_files(
+        &self,
+        game_dir: &Path,
+        other_mod_dir: impl Fn(&str) -> Option<PathBuf>,
+    ) -> Result<Vec<ContentFile>, Box<dyn std::error::Error>> {
+        let mod_dir = Path::new(self.home_dir.as_deref().ok_or("Mod home dir not set")?);
+        let xml = fs::read_to_string(mod_dir.join(MOD_FILELIST_FILE))?;
+        parse_content_files(&xml, mod_dir, game_dir, other_mod_dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_content_files() {
+        let game = TempDir::new().unwrap();
+        let mod_dir = game.path().join("LocalMods").join("Test");
+        fs::create_dir_all(mod_dir.join("Items")).unwrap();
+        fs::write(mod_dir.join("Items").join("items.xml"), "<Items />").unwrap();
+        let other_dir = game.path().join("LocalMods").join("2559634234");
+
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<contentpackage name="Test" steamworkshopid="" modversion="1.0.0">
+  <Item file="%ModDir%/Items/items.xml" />
+  <Character file="%moddir%\Characters\Crawler\Crawler.xml">
+    <Item file="%ModDir%/nested.xml" />
+  </Character>
+  <Text file="%ModDir:2559634234%/Text/English.xml" />
+  <Submarine file="%ModDir:Not Installed%/Subs/Sub.sub" />
+  <Other file="Content/Items/Weapons/weapons.xml" />
+  <Afflictions />
+</contentpackage>"#;
+        let files = parse_content_files(xml, &mod_dir, game.path(), |name| {
+            (name == "2559634234").then(|| other_dir.clone())
+        })
+        .unwrap();
+
+        assert_eq!(files.len(), 5);
+        assert_eq!(files[0].element_type, "Item");
+        assert_eq!(files[0].path, Some(mod_dir.join("Items").join("items.xml")));
+        assert!(files[0].exists);
+        assert_eq!(files[1].element_type, "Character");
+        assert_eq!(
+            files[1].path,
+            Some(
+                mod_dir
+                    .join("Characters")
+                    .join("Crawler")
+                    .join("Crawler.xml")
+            )
+        );
+        assert!(!files[1].exists);
+        assert_eq!(
+            files[2].path,
+            Some(other_dir.join("Text").join("English.xml"))
+        );
+        assert_eq!(files[3].path, None);
+        assert_eq!(files[3].file, "%ModDir:Not Installed%/Subs/Sub.sub");
+        assert_eq!(
+            files[4].path,
+            Some(game.path().join("Content/Items/Weapons/weapons.xml"))
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use crate::mods::{BarotraumaMod, GameCompatibility};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A Barotrauma version such as `1.9.8.0`: major, minor, build and revision.
+///
+/// Versions with fewer parts are padded with zeros, so `1.9` equals `1.9.0.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct GameVersion(pub [u32; 4]);
+
+impl GameVersion {
+    /// The vanilla content package, relative to the game directory. Its `gameversion` is
+    /// the version of the install.
+    pub const VANILLA_CONTENT_PACKAGE: &'static str = "Content/ContentPackages/Vanilla.xml";
+
+    /// Reads the installed game's version from the vanilla content package of the
+    /// install at `game_dir`.
+    pub fn detect<P: AsRef<Path>>(game_dir: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let vanilla =
+            BarotraumaMod::from_path(game_dir.as_ref().join(Self::VANILLA_CONTENT_PACKAGE))?;
+        Ok(vanilla.game_version.parse()?)
+    }
+
+    /// How a mod built for `mod_version` relates to this game version.
+    ///
+    /// Barotrauma breaks content compatibility in its minor releases, so a mod built for
+    /// the same major and minor version is [`GameCompatibility::Compatible`].
+    pub fn compatibility_of(&self, mod_version: &GameVersion) -> GameCompatibility {
+        if mod_version.0[..2] < self.0[..2] {
+            GameCompatibility::Older
+        } else if mod_version > self {
+            GameCompatibility::Newer
+        } else {
+            GameCompatibility::Compatible
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("invalid game version {0:?}")]
+pub struct ParseGameVersionError(String);
+
+impl FromStr for GameVersion {
+    type Err = ParseGameVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split('.').collect();
+        if parts.len() > 4 {
+            return Err(ParseGameVersionError(s.to_string()));
+        }
+        let mut version = [0; 4];
+        for (part, number) in parts.iter().zip(version.iter_mut()) {
+            *number = part
+                .parse()
+                .map_err(|_| ParseGameVersionError(s.to_string()))?;
+        }
+        Ok(GameVersion(version))
+    }
+}
+
+impl fmt::Display for GameVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [major, minor, build, revision] = self.0;
+        write!(f, "{major}.{minor}.{build}.{revision}")
+    }
+}
+
+impl Serialize for GameVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl BarotraumaMod {
+    /// Compatibility of the mod with the installed `game_version`, `None` when the mod
+    /// declares no valid `gameversion`.
+    pub fn compatibility_with(&self, game_version: &GameVersion) -> Option<GameCompatibility> {
+        let mod_version: GameVersion = self.game_version.parse().ok()?;
+        Some(game_version.compatibility_of(&mod_version))
+    }
+
+    /// Records [`BarotraumaMod::compatibility_with`] in `game_compatibility`.
+    pub fn update_game_compatibility(&mut self, game_version: &GameVersion) -> &mut Self {
+        self.game_compatibility = self
+            .compatibility_with(game_version)
+            .map(|compatibility| compatibility as i32);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_game_version() {
+        let version: GameVersion = "1.9.8.0".parse().unwrap();
+        assert_eq!(version, GameVersion([1, 9, 8, 0]));
+        assert_eq!(
+            "1.10".parse::<GameVersion>().unwrap().to_string(),
+            "1.10.0.0"
+        );
+        assert!("1.9.8.0.1".parse::<GameVersion>().is_err());
+        assert!("".parse::<GameVersion>().is_err());
+        // Compared numerically, not as strings
+        assert!("1.10.0.0".parse::<GameVersion>().unwrap() > version);
+    }
+
+    #[test]
+    fn test_compatibility() {
+        let game = GameVersion([1, 9, 8, 0]);
+        let compatibility = |version: &str| game.compatibility_of(&version.parse().unwrap());
+        assert_eq!(compatibility("1.9.8.0"), GameCompatibility::Compatible);
+        assert_eq!(compatibility("1.9.7.1"), GameCompatibility::Compatible);
+        assert_eq!(compatibility("1.8.8.1"), GameCompatibility::Older);
+        assert_eq!(compatibility("1.9.8.1"), GameCompatibility::Newer);
+        assert_eq!(compatibility("1.10.0.0"), GameCompatibility::Newer);
+        assert_eq!(compatibility("2.0"), GameCompatibility::Newer);
+
+        let mut mod_obj = BarotraumaMod {
+            game_version: "0.19.11.0".to_string(),
+            ..Default::default()
+        };
+        mod_obj.update_game_compatibility(&game);
+        assert_eq!(
+            mod_obj.game_compatibility,
+            Some(GameCompatibility::Older as i32)
+        );
+        mod_obj.game_version = String::new();
+        mod_obj.update_game_compatibility(&game);
+        assert_eq!(mod_obj.game_compatibility, None);
+    }
+}
@@ -3,17 +3,31 @@ mod mod_analyzer;
 mod mod_list;
 
 mod config_analyzer;
+mod conflicts;
+mod content;
 mod de;
+mod game_version;
 mod mods;
+mod package_hash;
 mod retrieve;
+mod validate;
 
 #[allow(unused)]
-pub(crate) use de::{deserialize_bool, deserialize_u64};
+pub(crate) use de::{deserialize_bool, deserialize_optional_u64, deserialize_u64};
 
-pub use mod_analyzer::{ModDependency, parse_dependencies};
+pub use game_version::{GameVersion, ParseGameVersionError};
+pub use mod_analyzer::{DependencySource, ModDependency, merge_dependencies, parse_dependencies};
 pub use mods::*;
 
+pub use conflicts::{
+    ConflictKind, ConflictingDefinition, ContentConflict, IDENTIFIED_CONTENT_TYPES,
+    PrefabDefinition, detect_content_conflicts, parse_prefab_definitions,
+};
+pub use content::{ContentFile, parse_content_files};
+
 pub use manage::BarotraumaModManager;
 
 pub use config_analyzer::{BaroConfig, ModEntry};
-pub use retrieve::retrieve_mod_metadata;
+pub use package_hash::{HashCheck, HashStatus, content_file_hash, content_package_hash};
+pub use retrieve::{retrieve_change_notes, retrieve_creator_profiles, retrieve_mod_metadata};
+pub use validate::{FileDiagnostic, FileIssue, ModValidation, check_xml};
@@ -1,7 +1,10 @@
 use crate::config_analyzer::BaroConfig;
 use crate::retrieve::retrieve_mod_metadata;
-use crate::{BarotraumaMod, ModList};
+use crate::{
+    BarotraumaMod, ContentFile, GameVersion, HashCheck, HashStatus, ModList, ModValidation,
+};
 use constants::BarotraumaHome;
+use logger::debug;
 use rayon::prelude::*;
 use std::path::PathBuf;
 use steam_api::SteamWorkShopClient;
@@ -137,9 +140,114 @@ impl BarotraumaModManager {
         }
     }
 
+    /// The content files of an installed mod, with `%ModDir:<name>%` resolved against
+    /// the other installed mods.
+    pub fn get_mod_content_files(&self, mod_id: u64) -> Result<Vec<ContentFile>, String> {
+        let game_home = self.game_home.as_ref().ok_or("Game home not set")?;
+        let target_mod = self
+            .mods
+            .iter()
+            .rfind(|mod_obj| mod_obj.steam_workshop_id == mod_id)
+            .ok_or("Mod not found")?;
+        target_mod
+            .content_files(game_home.home_dir(), |name| self.installed_mod_dir(name))
+            .map_err(|e| format!("{e}, failed to read content files of mod {mod_id}."))
+    }
+
+    /// Checks the files an installed mod references, see [`BarotraumaMod::validate`], and
+    /// records the resulting health score on the mod.
+    pub fn validate_mod(&mut self, mod_id: u64) -> Result<ModValidation, String> {
+        let game_home = self.game_home.as_ref().ok_or("Game home not set")?;
+        let index = self
+            .mods
+            .iter()
+            .rposition(|mod_obj| mod_obj.steam_workshop_id == mod_id)
+            .ok_or("Mod not found")?;
+        let validation = self.mods[index]
+            .validate(game_home.home_dir(), |name| self.installed_mod_dir(name))
+            .map_err(|e| format!("{e}, failed to validate mod {mod_id}."))?;
+        self.mods[index].health_score = Some(validation.health_score);
+        Ok(validation)
+    }
+
+    /// The enabled mods in load order with their content files, for
+    /// [`detect_content_conflicts`](crate::detect_content_conflicts).
+    ///
+    /// Mods whose filelist.xml cannot be read are left out.
+    pub fn enabled_mod_content_files(
+        &self,
+    ) -> Result<Vec<(BarotraumaMod, Vec<ContentFile>)>, String> {
+        let game_home = self.game_home.as_ref().ok_or("Game home not set")?;
+        Ok(self
+            .enabled_mods()?
+            .into_iter()
+            .filter_map(|mod_obj| {
+                let files = mod_obj
+                    .content_files(game_home.home_dir(), |name| self.installed_mod_dir(name))
+                    .ok()?;
+                Some((mod_obj, files))
+            })
+            .collect())
+    }
+
+    /// Compares every installed mod with the `expectedhash` of its filelist.xml, see
+    /// [`BarotraumaMod::verify_expected_hash`].
+    pub fn verify_expected_hashes(&self) -> Result<Vec<HashCheck>, String> {
+        let game_home = self.game_home.as_ref().ok_or("Game home not set")?;
+        Ok(self
+            .mods
+            .par_iter()
+            .map(|mod_obj| {
+                match mod_obj
+                    .content_files(game_home.home_dir(), |name| self.installed_mod_dir(name))
+                {
+                    Ok(files) => mod_obj.verify_expected_hash(&files),
+                    Err(e) => HashCheck {
+                        mod_name: mod_obj.name.clone(),
+                        mod_steam_id: mod_obj.steam_workshop_id,
+                        expected_hash: mod_obj.expected_hash.clone(),
+                        actual_hash: None,
+                        status: HashStatus::Error {
+                            message: e.to_string(),
+                        },
+                    },
+                }
+            })
+            .collect())
+    }
+
+    /// Directory of the installed mod named `name` or with Steam Workshop ID `name`.
+    fn installed_mod_dir(&self, name: &str) -> Option<PathBuf> {
+        self.mods
+            .iter()
+            .find(|mod_obj| mod_obj.name == name || mod_obj.steam_workshop_id.to_string() == name)
+            .and_then(|mod_obj| mod_obj.home_dir.as_ref().map(PathBuf::from))
+    }
+
+    /// The version of the installed game, read from its vanilla content package.
+    pub fn game_version(&self) -> Result<GameVersion, String> {
+        let game_home = self.game_home.as_ref().ok_or("Game home not set")?;
+        GameVersion::detect(game_home.home_dir())
+            .map_err(|e| format!("{e}, failed to detect the game version."))
+    }
+
+    /// Records every mod's compatibility with the installed game, see
+    /// [`BarotraumaMod::update_game_compatibility`]. Leaves the mods untouched when the
+    /// game version cannot be detected.
+    fn set_game_compatibility(&self, mods: &mut [BarotraumaMod]) {
+        match self.game_version() {
+            Ok(game_version) => mods.iter_mut().for_each(|mod_obj| {
+                mod_obj.update_game_compatibility(&game_version);
+            }),
+            Err(e) => debug!("{}", e),
+        }
+    }
+
     pub fn refresh_mods(&mut self) -> Result<&mut Self, String> {
         if let Some(ref game_home) = self.game_home {
-            self.mods = BarotraumaModManager::discover_mods(game_home.mod_dir());
+            let mut mods = BarotraumaModManager::discover_mods(game_home.mod_dir());
+            self.set_game_compatibility(&mut mods);
+            self.mods = mods;
             Ok(self)
         } else {
             Err("Game home not set".to_string())
@@ -156,7 +264,7 @@ impl BarotraumaModManager {
                     )
                 })?;
 
-            Ok(conf
+            let mut mods = conf
                 .mods()
                 .into_iter()
                 .map(|mod_entry| {
@@ -165,7 +273,9 @@ impl BarotraumaModManager {
                     BarotraumaMod::from_path(p)
                 })
                 .filter_map(Result::ok)
-                .collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            self.set_game_compatibility(&mut mods);
+            Ok(mods)
         } else {
             Err("Game home not set".to_string())
         }
@@ -9,14 +9,31 @@ use std::fs;
 use std::path::Path;
 use walkdir::WalkDir;
 
-/// A mod dependency declared via a `<package>` element in content.xml.
+/// Where a [`ModDependency`] was declared.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DependencySource {
+    /// A `<package>` element in content.xml.
+    #[default]
+    Xml,
+    /// A "Required Item" of the mod's Steam Workshop page.
+    Workshop,
+    /// Declared in content.xml and on the Workshop.
+    Both,
+}
+
+/// A mod dependency declared via a `<package>` element in content.xml or as a
+/// Steam Workshop "Required Item".
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModDependency {
-    /// The name of the dependency mod.
+    /// The name of the dependency mod, the Workshop ID for dependencies only known
+    /// from the Workshop.
     pub name: String,
     /// The Steam Workshop ID of the dependency, if specified.
     pub steam_workshop_id: Option<u64>,
+    #[serde(default)]
+    pub source: DependencySource,
 }
 
 /// Parses `<package>` dependency declarations from a Barotrauma content.xml string.
@@ -34,34 +51,35 @@ pub fn parse_dependencies(xml: &str) -> Result<Vec<ModDependency>, Box<dyn std::
 
     loop {
         match reader.read_event_into(&mut buf) {
-            Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e)) => {
-                if e.name().as_ref() == b"package" {
-                    let mut name = String::new();
-                    let mut id: Option<u64> = None;
-                    for attr in e.attributes().flatten() {
-                        match attr.key.as_ref() {
-                            b"name" => {
-                                name = attr.decoded_and_normalized_value(XmlVersion::Implicit1_0, reader.decoder())
-                                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?
-                                    .into_owned();
-                            }
-                            b"id" => {
-                                let val = attr.decoded_and_normalized_value(XmlVersion::Implicit1_0, reader.decoder())
-                                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
-                                if !val.is_empty() {
-                                    id = Some(val.parse::<u64>()?);
-                                }
+            Ok(Event::Empty(ref e)) | Ok(Event::Start(ref e))
+                if e.name().as_ref() == b"package" =>
+            {
+                let mut name = String::new();
+                let mut id: Option<u64> = None;
+                for attr in e.attributes().flatten() {
+                    match attr.key.as_ref() {
+                        b"name" => {
+                            name = attr.decoded_and_normalized_value(XmlVersion::Implicit1_0, reader.decoder())
+                                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?
+                                .into_owned();
+                        }
+                        b"id" => {
+                            let val = attr.decoded_and_normalized_value(XmlVersion::Implicit1_0, reader.decoder())
+                                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+                            if !val.is_empty() {
+                                id = Some(val.parse::<u64>()?);
                             }
-                            _ => {}
                         }
-                    }
-                    if !name.is_empty() {
-                        deps.push(ModDependency {
-                            name,
-                            steam_workshop_id: id,
-                        });
+                        _ => {}
                     }
                 }
+                if !name.is_empty() {
+                    deps.push(ModDependency {
+                        name,
+                        steam_workshop_id: id,
+                        source: DependencySource::Xml,
+                    });
+                }
             }
             Ok(Event::Eof) => break,
             Err(e) => return Err(Box::new(e)),
@@ -72,6 +90,36 @@ pub fn parse_dependencies(xml: &str) -> Result<Vec<ModDependency>, Box<dyn std::
     Ok(deps)
 }
 
+/// Merges the dependencies `declared` in content.xml with the Workshop `required_items`
+/// of the same mod.
+///
+/// Dependencies found in both keep their XML name and become [`DependencySource::Both`],
+/// required items missing from content.xml are appended as [`DependencySource::Workshop`].
+pub fn merge_dependencies(
+    declared: Vec<ModDependency>,
+    required_items: &[u64],
+) -> Vec<ModDependency> {
+    let mut deps = declared;
+    for &item_id in required_items {
+        let mut found = false;
+        for dep in deps
+            .iter_mut()
+            .filter(|dep| dep.steam_workshop_id == Some(item_id))
+        {
+            dep.source = DependencySource::Both;
+            found = true;
+        }
+        if !found {
+            deps.push(ModDependency {
+                name: item_id.to_string(),
+                steam_workshop_id: Some(item_id),
+                source: DependencySource::Workshop,
+            });
+        }
+    }
+    deps
+}
+
 impl BarotraumaMod {
     pub fn set_home_dir(&mut self, home_dir: String) -> &mut Self {
         self.home_dir = Some(home_dir);
@@ -207,15 +255,49 @@ mod tests {
     fn test_bool_deserialization() {
         let xml_true = r#"<contentpackage name="test" modversion="1" corepackage="True" steamworkshopid="" gameversion="" expectedhash="" />"#;
         let mod_obj = BarotraumaMod::from_xml_string(xml_true).expect("Should parse 'True'");
-        assert_eq!(mod_obj.core_package, true);
+        assert!(mod_obj.core_package);
 
         let xml_false = r#"<contentpackage name="test" modversion="1" corepackage="False" steamworkshopid="" gameversion="" expectedhash="" />"#;
         let mod_obj = BarotraumaMod::from_xml_string(xml_false).expect("Should parse 'False'");
-        assert_eq!(mod_obj.core_package, false);
+        assert!(!mod_obj.core_package);
 
         let xml_mixed = r#"<contentpackage name="test" modversion="1" corepackage="false" steamworkshopid="" gameversion="" expectedhash="" />"#;
         let mod_obj = BarotraumaMod::from_xml_string(xml_mixed).expect("Should parse 'false'");
-        assert_eq!(mod_obj.core_package, false);
+        assert!(!mod_obj.core_package);
+    }
+
+    #[test]
+    fn test_install_time() {
+        let xml = r#"<contentpackage name="test" steamworkshopid="42" installtime="1700000200" />"#;
+        let mod_obj = BarotraumaMod::from_xml_string(xml).expect("Should parse installtime");
+        assert_eq!(mod_obj.last_modified, Some(1700000200));
+
+        let xml = r#"<contentpackage name="test" steamworkshopid="42" />"#;
+        let mod_obj =
+            BarotraumaMod::from_xml_string(xml).expect("Should parse without installtime");
+        assert_eq!(mod_obj.last_modified, None);
+    }
+
+    #[test]
+    fn test_merge_dependencies() {
+        let xml = r#"<contentpackage name="test">
+  <package name="Lua For Barotrauma" id="2559634234" />
+  <package name="Local Only" />
+</contentpackage>"#;
+        let declared = parse_dependencies(xml).unwrap();
+        assert!(
+            declared
+                .iter()
+                .all(|dep| dep.source == DependencySource::Xml)
+        );
+
+        let deps = merge_dependencies(declared, &[2559634234, 2701251094]);
+        assert_eq!(deps.len(), 3);
+        assert_eq!(deps[0].source, DependencySource::Both);
+        assert_eq!(deps[1].source, DependencySource::Xml);
+        assert_eq!(deps[2].name, "2701251094");
+        assert_eq!(deps[2].steam_workshop_id, Some(2701251094));
+        assert_eq!(deps[2].source, DependencySource::Workshop);
     }
 
     #[test]
@@ -226,7 +308,7 @@ mod tests {
         // Basic metadata
         assert_eq!(mod_obj.name, "BaroTraumatic");
         assert_eq!(mod_obj.mod_version, "1.2.81");
-        assert_eq!(mod_obj.core_package, false);
+        assert!(!mod_obj.core_package);
         assert_eq!(mod_obj.steam_workshop_id, 2518816103);
         assert_eq!(mod_obj.game_version, "1.9.8.0");
         assert_eq!(mod_obj.expected_hash, "9A54ACF2E7EBC95726A72AE966EF5F8D");
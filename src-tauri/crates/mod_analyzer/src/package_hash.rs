@@ -0,0 +1,191 @@
+use crate::content::ContentFile;
+use crate::mods::BarotraumaMod;
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Extensions of the files the game hashes as text, with all whitespace removed so
+/// line endings and indentation do not matter.
+const TEXT_EXTENSIONS: [&str; 2] = ["xml", "txt"];
+
+/// MD5 of a single content file, as the game's `Md5Hash.CalculateForFile` computes it.
+///
+/// Text files are decoded as UTF-8 without BOM and hashed without whitespace, every
+/// other file is hashed byte for byte.
+pub fn content_file_hash(path: &Path) -> io::Result<[u8; 16]> {
+    let data = fs::read(path)?;
+    let is_text = path.extension().is_some_and(|extension| {
+        TEXT_EXTENSIONS
+            .iter()
+            .any(|text| extension.eq_ignore_ascii_case(text))
+    });
+    if !is_text {
+        return Ok(Md5::digest(&data).into());
+    }
+    let text = String::from_utf8_lossy(&data);
+    let stripped: String = text
+        .trim_start_matches('\u{feff}')
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    Ok(Md5::digest(stripped.as_bytes()).into())
+}
+
+/// The content package hash Barotrauma writes to `expectedhash`, as an uppercase hex
+/// string.
+///
+/// Like the game's `ContentPackage.CalculateHash`, this is the MD5 over the hashes of
+/// `files` in filelist.xml order, followed by the package `name` (when not empty) and
+/// `mod_version`.
+pub fn content_package_hash(
+    name: &str,
+    mod_version: &str,
+    files: &[ContentFile],
+) -> io::Result<String> {
+    let mut hasher = Md5::new();
+    for file in files {
+        let path = file.path.as_deref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} refers to a mod that is not installed", file.file),
+            )
+        })?;
+        hasher.update(content_file_hash(path)?);
+    }
+    if !name.is_empty() {
+        hasher.update(name.as_bytes());
+    }
+    hasher.update(mod_version.as_bytes());
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect())
+}
+
+/// Outcome of comparing a mod with its declared `expectedhash`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum HashStatus {
+    Match,
+    /// The contents changed since the package was published, or were copied only partly.
+    Mismatch,
+    /// filelist.xml declares no `expectedhash`.
+    NotDeclared,
+    /// The hash could not be computed, usually because a content file is missing.
+    Error {
+        message: String,
+    },
+}
+
+/// Result of [`BarotraumaMod::verify_expected_hash`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashCheck {
+    pub mod_name: String,
+    pub mod_steam_id: u64,
+    pub expected_hash: String,
+    pub actual_hash: Option<String>,
+    pub status: HashStatus,
+}
+
+impl BarotraumaMod {
+    /// Computes the mod's [`content_package_hash`] from its `files` and compares it with
+    /// the `expectedhash` of its filelist.xml.
+    pub fn verify_expected_hash(&self, files: &[ContentFile]) -> HashCheck {
+        let (actual_hash, status) = match content_package_hash(&self.name, &self.mod_version, files)
+        {
+            Ok(_) if self.expected_hash.is_empty() => (None, HashStatus::NotDeclared),
+            Ok(hash) if hash.eq_ignore_ascii_case(&self.expected_hash) => {
+                (Some(hash), HashStatus::Match)
+            }
+            Ok(hash) => (Some(hash), HashStatus::Mismatch),
+            Err(e) => (
+                None,
+                HashStatus::Error {
+                    message: e.to_string(),
+                },
+            ),
+        };
+        HashCheck {
+            mod_name: self.name.clone(),
+            mod_steam_id: self.steam_workshop_id,
+            expected_hash: self.expected_hash.clone(),
+            actual_hash,
+            status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::parse_content_files;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_text_hash_ignores_whitespace() {
+        let dir = TempDir::new().unwrap();
+        let unix = dir.path().join("unix.xml");
+        let windows = dir.path().join("windows.XML");
+        let binary = dir.path().join("binary.sub");
+        fs::write(&unix, "<Items>\n  <Item identifier=\"a\" />\n</Items>\n").unwrap();
+        fs::write(
+            &windows,
+            "\u{feff}<Items>\r\n\t<Item identifier=\"a\" />\r\n</Items>",
+        )
+        .unwrap();
+        fs::write(&binary, "<Items> </Items>").unwrap();
+
+        let expected: [u8; 16] = Md5::digest(b"<Items><Itemidentifier=\"a\"/></Items>").into();
+        assert_eq!(content_file_hash(&unix).unwrap(), expected);
+        assert_eq!(content_file_hash(&windows).unwrap(), expected);
+        let raw: [u8; 16] = Md5::digest(b"<Items> </Items>").into();
+        assert_eq!(content_file_hash(&binary).unwrap(), raw);
+    }
+
+    #[test]
+    fn test_verify_expected_hash() {
+        let game = TempDir::new().unwrap();
+        let mod_dir = game.path().join("Test");
+        fs::create_dir_all(&mod_dir).unwrap();
+        fs::write(mod_dir.join("items.xml"), "<Items />").unwrap();
+        let xml = r#"<contentpackage name="Test" modversion="1.0.0">
+  <Item file="%ModDir%/items.xml" />
+</contentpackage>"#;
+        let files = parse_content_files(xml, &mod_dir, game.path(), |_| None).unwrap();
+
+        let mut hasher = Md5::new();
+        hasher.update(Md5::digest(b"<Items/>"));
+        hasher.update(b"Test1.0.0");
+        let expected: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect();
+        assert_eq!(
+            content_package_hash("Test", "1.0.0", &files).unwrap(),
+            expected
+        );
+
+        let mut mod_obj = BarotraumaMod::from_xml_string(xml).unwrap();
+        mod_obj.expected_hash = expected.to_lowercase();
+        assert_eq!(
+            mod_obj.verify_expected_hash(&files).status,
+            HashStatus::Match
+        );
+
+        fs::write(mod_dir.join("items.xml"), "<Items><Item /></Items>").unwrap();
+        assert_eq!(
+            mod_obj.verify_expected_hash(&files).status,
+            HashStatus::Mismatch
+        );
+        fs::remove_file(mod_dir.join("items.xml")).unwrap();
+        assert!(matches!(
+            mod_obj.verify_expected_hash(&files).status,
+            HashStatus::Error { .. }
+        ));
+    }
+}
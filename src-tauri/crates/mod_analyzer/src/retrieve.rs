@@ -1,39 +1,247 @@
-use crate::BarotraumaMod;
-use logger::info;
+use crate::{BarotraumaMod, CreatorProfile, MetadataSource, WorkshopStatus};
+use logger::{info, warn};
 use std::collections::HashMap;
-use steam_api::{SteamWorkShopClient, WorkshopItem};
+use steam_api::{
+    Cached, ChangeNote, ItemOutcome, PlayerSummariesClient, PlayerSummary, Source,
+    SteamWorkShopClient,
+};
+
+/// Fills in workshop metadata of `mods`.
+///
+/// Mods whose workshop page was removed, made private or banned keep their local data
+/// and get `workshop_status` / `workshop_result` set instead of failing the lookup.
+/// Lookups go through the client's metadata cache; `metadata_source` tells whether a
+/// mod's metadata came from Steam or from the cache. Local mods without a Steam
+/// Workshop ID are returned unchanged.
 pub async fn retrieve_mod_metadata(
-    mods: Vec<BarotraumaMod>,
+    mut mods: Vec<BarotraumaMod>,
     batch_size: usize,
     client: &SteamWorkShopClient,
 ) -> Result<Vec<BarotraumaMod>, Box<dyn std::error::Error>> {
-    info!("Retrieving mod metadata for {} mods", mods.len());
-    let retrieved: Vec<WorkshopItem> = client
-        .get_items_batched(
-            mods.iter()
-                .map(|baro_mod| baro_mod.steam_workshop_id)
-                .collect::<Vec<u64>>(),
-            batch_size,
-        )
+    let mut by_id: HashMap<u64, Vec<usize>> = HashMap::new();
+    mods.iter()
+        .enumerate()
+        .filter(|(_, baro_mod)| baro_mod.steam_workshop_id > 0)
+        .for_each(|(index, baro_mod)| {
+            by_id
+                .entry(baro_mod.steam_workshop_id)
+                .or_default()
+                .push(index)
+        });
+    if by_id.is_empty() {
+        return Ok(mods);
+    }
+
+    info!("Retrieving mod metadata for {} mods", by_id.len());
+    let retrieved = client
+        .get_item_outcomes_cached(by_id.keys().copied().collect::<Vec<u64>>(), batch_size)
         .await
         .map_err(|e| format!("{}, failed to retrieve mod metadata.", e))?;
 
-    let mut mapping = mods
-        .into_iter()
-        .map(|baro_mod| (baro_mod.steam_workshop_id, baro_mod))
-        .collect::<HashMap<u64, BarotraumaMod>>();
-    retrieved.into_iter().for_each(|item| {
-        if let Some(baro_mod) = mapping.get_mut(&item.published_file_id) {
+    retrieved.iter().for_each(|cached| {
+        for &index in by_id.get(&cached.value.item_id()).into_iter().flatten() {
+            apply_outcome(&mut mods[index], cached);
+        }
+    });
+
+    Ok(mods)
+}
+
+/// Copies the workshop metadata of one lookup into `baro_mod`.
+fn apply_outcome(baro_mod: &mut BarotraumaMod, cached: &Cached<ItemOutcome>) {
+    baro_mod.set_metadata_source(match cached.source {
+        Source::Network => MetadataSource::Network,
+        Source::Cache => MetadataSource::Cache,
+        Source::Stale => MetadataSource::Stale,
+    });
+    baro_mod.metadata_fetched_at = Some(cached.fetched_at);
+    let status = match &cached.value {
+        ItemOutcome::Found(item) => {
             baro_mod.size = item.file_size.into();
-            baro_mod.last_modified = item.time_updated.into();
+            baro_mod.workshop_time_updated = item.time_updated.into();
             baro_mod.description = item.description.clone().into();
             baro_mod.preview_image = item.preview_url().to_string().into();
+            baro_mod.preview_hash =
+                Some(item.hcontent_preview.clone()).filter(|hash| !hash.is_empty());
             baro_mod.subscribers = item.subscriptions.into();
             baro_mod.likes = item.favorited.into();
             baro_mod.creator = item.creator.into();
-            baro_mod.tags = item.tags.into_iter().map(|tag| tag.tag).collect();
+            baro_mod.tags = item.tags.iter().map(|tag| tag.tag.clone()).collect();
+            baro_mod.workshop_status = None;
+            baro_mod.workshop_result = None;
+            return;
+        }
+        ItemOutcome::NotFound { result, .. } => (WorkshopStatus::NotFound, *result),
+        ItemOutcome::Private { result, .. } => (WorkshopStatus::Private, *result),
+        ItemOutcome::Banned { result, .. } => (WorkshopStatus::Banned, *result),
+    };
+    warn!(
+        "Workshop page of {} ({}) is unavailable: {}",
+        baro_mod.name,
+        baro_mod.steam_workshop_id,
+        status.0.as_str_name()
+    );
+    baro_mod.set_workshop_status(status.0);
+    baro_mod.workshop_result = Some(status.1);
+}
+
+/// Fetches the Workshop change notes of `item_id`, newest first.
+///
+/// With the `installed` copy of the item only the notes published after its
+/// `last_modified` are returned. Otherwise the whole history is returned.
+pub async fn retrieve_change_notes(
+    item_id: u64,
+    installed: Option<&BarotraumaMod>,
+    client: &SteamWorkShopClient,
+) -> Result<Vec<ChangeNote>, Box<dyn std::error::Error>> {
+    let since = installed.and_then(|baro_mod| baro_mod.last_modified);
+    Ok(client
+        .get_change_notes(item_id, since)
+        .await
+        .map_err(|e| format!("{}, failed to retrieve change notes.", e))?)
+}
+
+impl From<PlayerSummary> for CreatorProfile {
+    fn from(player: PlayerSummary) -> Self {
+        CreatorProfile {
+            steam_id: player.steam_id.to_string(),
+            persona_name: player.persona_name,
+            avatar_url: player.avatar_url,
+            profile_url: player.profile_url,
         }
+    }
+}
+
+/// Fills in `creator_profile` of the `mods` whose `creator` is known.
+///
+/// Run it after [`retrieve_mod_metadata`], which sets `creator`. Creators without a
+/// public profile leave `creator_profile` unset.
+pub async fn retrieve_creator_profiles(
+    mods: &mut [BarotraumaMod],
+    client: &PlayerSummariesClient,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let creators = mods
+        .iter()
+        .filter_map(|baro_mod| baro_mod.creator)
+        .collect::<Vec<u64>>();
+    let profiles = client
+        .get_player_summaries(&creators)
+        .await
+        .map_err(|e| format!("{}, failed to retrieve creator profiles.", e))?
+        .into_iter()
+        .map(|player| (player.steam_id, CreatorProfile::from(player)))
+        .collect::<HashMap<u64, CreatorProfile>>();
+
+    mods.iter_mut().for_each(|baro_mod| {
+        baro_mod.creator_profile = baro_mod
+            .creator
+            .and_then(|creator| profiles.get(&creator).cloned());
     });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+
+    const PATH: &str = "/ISteamRemoteStorage/GetPublishedFileDetails/v1/";
+
+    #[tokio::test]
+    async fn test_local_mods_are_not_looked_up() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", PATH)
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("itemcount".into(), "1".into()),
+                Matcher::UrlEncoded("publishedfileids[0]".into(), "42".into()),
+            ]))
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(
+                json!({
+                    "response": {
+                        "result": 1,
+                        "resultcount": 1,
+                        "publishedfiledetails": [
+                            { "publishedfileid": "42", "result": 1, "title": "Workshop", "time_updated": 1700000000 }
+                        ]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let local = |name: &str| BarotraumaMod {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let mods = vec![
+            local("Local A"),
+            BarotraumaMod {
+                name: "Workshop".to_string(),
+                steam_workshop_id: 42,
+                last_modified: Some(1600000000),
+                ..Default::default()
+            },
+            local("Local B"),
+        ];
+        let client = SteamWorkShopClient::from_endpoint(format!("{}{PATH}", server.url()));
+        let mods = retrieve_mod_metadata(mods, 50, &client).await.unwrap();
+
+        mock.assert();
+        let names: Vec<&str> = mods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["Local A", "Workshop", "Local B"]);
+        assert_eq!(mods[1].workshop_time_updated, Some(1700000000));
+        // The local install time is kept
+        assert_eq!(mods[1].last_modified, Some(1600000000));
+        assert_eq!(mods[1].metadata_source(), MetadataSource::Network);
+        for local in [&mods[0], &mods[2]] {
+            assert_eq!(local.workshop_status, None);
+            assert_eq!(local.metadata_source, None);
+        }
+    }
+
+    fn change_note(timestamp: u64, text: &str) -> String {
+        format!(r#"<div class="changelog headline">Update</div><p id="{timestamp}">{text}</p>"#)
+    }
+
+    #[tokio::test]
+    async fn test_change_notes_since_install() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/changelog/42")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body(
+                [
+                    change_note(1_700_000_300, "Third"),
+                    change_note(1_700_000_200, "Second"),
+                    change_note(1_700_000_100, "First"),
+                ]
+                .concat(),
+            )
+            .create();
+        let client = SteamWorkShopClient::new()
+            .with_changelog_endpoint(format!("{}/changelog/", server.url()));
+
+        // The installed copy came from the second update
+        let installed = BarotraumaMod {
+            steam_workshop_id: 42,
+            last_modified: Some(1_700_000_200),
+            workshop_time_updated: Some(1_700_000_300),
+            ..Default::default()
+        };
+
+        let notes = retrieve_change_notes(42, Some(&installed), &client)
+            .await
+            .unwrap();
+        let texts: Vec<&str> = notes.iter().map(|note| note.text.as_str()).collect();
+        assert_eq!(texts, vec!["Third"]);
 
-    Ok(mapping.into_values().collect::<Vec<BarotraumaMod>>())
+        // Without an installed copy the whole history is returned
+        let notes = retrieve_change_notes(42, None, &client).await.unwrap();
+        assert_eq!(notes.len(), 3);
+    }
 }
@@ -0,0 +1,279 @@
+use crate::content::{ContentFile, parse_content_files};
+use crate::mods::BarotraumaMod;
+use constants::MOD_FILELIST_FILE;
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What is wrong with a file referenced from filelist.xml.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum FileIssue {
+    /// The file does not exist.
+    Missing,
+    /// The file exists with a different letter case, which works on Windows but not on
+    /// case-sensitive file systems.
+    CaseMismatch { actual: PathBuf },
+    /// The path refers to a mod with `%ModDir:<name>%` that is not installed.
+    Unresolved,
+    /// The file is not well-formed XML, `line` and `column` start at 1.
+    MalformedXml {
+        message: String,
+        line: usize,
+        column: usize,
+    },
+}
+
+/// An issue found in one file, see [`FileIssue`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiagnostic {
+    /// The element referencing the file, `contentpackage` for filelist.xml itself.
+    pub element_type: String,
+    /// The file as written in filelist.xml.
+    pub file: String,
+    pub path: Option<PathBuf>,
+    pub issue: FileIssue,
+}
+
+/// Result of validating a mod's referenced files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModValidation {
+    pub mod_steam_id: u64,
+    /// Number of files referenced from filelist.xml.
+    pub files_checked: usize,
+    pub diagnostics: Vec<FileDiagnostic>,
+    /// Percentage of referenced files without issues, 0 when filelist.xml is malformed.
+    pub health_score: u32,
+}
+
+/// Checks that `xml` is well-formed, returning the error message and its 1-based
+/// line and column.
+pub fn check_xml(xml: &[u8]) -> Result<(), (String, usize, usize)> {
+    let mut reader = Reader::from_reader(xml);
+    let mut buf = Vec::new();
+    let mut depth = 0usize;
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(_)) => depth += 1,
+            Ok(Event::End(_)) => depth = depth.saturating_sub(1),
+            Ok(Event::Eof) if depth > 0 => {
+                let (line, column) = line_column(xml, xml.len());
+                return Err((
+                    format!("unexpected end of file, {depth} element(s) not closed"),
+                    line,
+                    column,
+                ));
+            }
+            Ok(Event::Eof) => return Ok(()),
+            Err(e) => {
+                let (line, column) = line_column(xml, reader.error_position() as usize);
+                return Err((e.to_string(), line, column));
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+/// 1-based line and column of the byte `offset` in `text`.
+fn line_column(text: &[u8], offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    let line = before.iter().filter(|&&b| b == b'\n').count() + 1;
+    let column = String::from_utf8_lossy(&before[line_start..])
+        .chars()
+        .count()
+        + 1;
+    (line, column)
+}
+
+/// Finds `relative` below `base` comparing names case-insensitively, preferring exact
+/// matches. Returns the path as it is on disk.
+fn find_ignoring_case(base: &Path, relative: &Path) -> Option<PathBuf> {
+    let mut found = base.to_path_buf();
+    for component in relative.components() {
+        let name = component.as_os_str();
+        let entries = fs::read_dir(&found).ok()?;
+        let mut candidate = None;
+        for entry in entries.flatten() {
+            let entry_name = entry.file_name();
+            if entry_name == name {
+                candidate = Some(entry_name);
+                break;
+            }
+            if candidate.is_none()
+                && entry_name
+                    .to_string_lossy()
+                    .eq_ignore_ascii_case(&name.to_string_lossy())
+            {
+                candidate = Some(entry_name);
+            }
+        }
+        found.push(candidate?);
+    }
+    Some(found)
+}
+
+/// Validates a single referenced file of the mod at `mod_dir`.
+fn check_file(file: &ContentFile, mod_dir: &Path) -> Option<FileIssue> {
+    let Some(path) = &file.path else {
+        return Some(FileIssue::Unresolved);
+    };
+    // Only the part inside the mod is the author's responsibility
+    if let Ok(relative) = path.strip_prefix(mod_dir) {
+        match find_ignoring_case(mod_dir, relative) {
+            None => return Some(FileIssue::Missing),
+            Some(actual) if actual != *path => return Some(FileIssue::CaseMismatch { actual }),
+            Some(_) => {}
+        }
+    } else if !file.exists {
+        return Some(FileIssue::Missing);
+    }
+
+    let is_xml = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("xml"));
+    if !is_xml {
+        return None;
+    }
+    match fs::read(path) {
+        Ok(xml) => check_xml(&xml)
+            .err()
+            .map(|(message, line, column)| FileIssue::MalformedXml {
+                message,
+                line,
+                column,
+            }),
+        Err(e) => Some(FileIssue::MalformedXml {
+            message: e.to_string(),
+            line: 1,
+            column: 1,
+        }),
+    }
+}
+
+impl BarotraumaMod {
+    /// Checks filelist.xml and every file it references for missing files, case
+    /// mismatched paths and malformed XML.
+    ///
+    /// # Arguments
+    /// * `game_dir` - The Barotrauma install directory.
+    /// * `other_mod_dir` - Looks up the directory of another mod by name or Steam Workshop ID.
+    pub fn validate(
+        &self,
+        game_dir: &Path,
+        other_mod_dir: impl Fn(&str) -> Option<PathBuf>,
+    ) -> Result<ModValidation, Box<dyn std::error::Error>> {
+        let mod_dir = Path::new(self.home_dir.as_deref().ok_or("Mod home dir not set")?);
+        let filelist = mod_dir.join(MOD_FILELIST_FILE);
+        let xml = fs::read(&filelist)?;
+        if let Err((message, line, column)) = check_xml(&xml) {
+            return Ok(ModValidation {
+                mod_steam_id: self.steam_workshop_id,
+                files_checked: 0,
+                diagnostics: vec![FileDiagnostic {
+                    element_type: "contentpackage".to_string(),
+                    file: MOD_FILELIST_FILE.to_string(),
+                    path: Some(filelist),
+                    issue: FileIssue::MalformedXml {
+                        message,
+                        line,
+                        column,
+                    },
+                }],
+                health_score: 0,
+            });
+        }
+
+        let xml = String::from_utf8_lossy(&xml);
+        let files = parse_content_files(
+            xml.trim_start_matches('\u{feff}'),
+            mod_dir,
+            game_dir,
+            other_mod_dir,
+        )?;
+        let diagnostics: Vec<FileDiagnostic> = files
+            .iter()
+            .filter_map(|file| {
+                check_file(file, mod_dir).map(|issue| FileDiagnostic {
+                    element_type: file.element_type.clone(),
+                    file: file.file.clone(),
+                    path: file.path.clone(),
+                    issue,
+                })
+            })
+            .collect();
+
+        // At most one diagnostic per file
+        let health_score = if files.is_empty() {
+            100
+        } else {
+            (100 * (files.len() - diagnostics.len()) / files.len()) as u32
+        };
+        Ok(ModValidation {
+            mod_steam_id: self.steam_workshop_id,
+            files_checked: files.len(),
+            diagnostics,
+            health_score,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_xml_position() {
+        assert!(check_xml(b"\xEF\xBB\xBF<Items><Item /></Items>").is_ok());
+        let (_, line, column) = check_xml(b"<Items>\n  <Item></Itme>\n</Items>").unwrap_err();
+        assert_eq!((line, column), (2, 9));
+        let (message, line, _) = check_xml(b"<Items>\n<Item />\n").unwrap_err();
+        assert!(message.contains("not closed"));
+        assert_eq!(line, 3);
+    }
+
+    #[test]
+    fn test_validate() {
+        let game = TempDir::new().unwrap();
+        let mod_dir = game.path().join("LocalMods").join("Test");
+        fs::create_dir_all(mod_dir.join("Items")).unwrap();
+        fs::write(mod_dir.join("Items").join("good.xml"), "<Items />").unwrap();
+        fs::write(mod_dir.join("Items").join("Case.xml"), "<Items />").unwrap();
+        fs::write(mod_dir.join("Items").join("bad.xml"), "<Items>\n<Item>").unwrap();
+        fs::write(
+            mod_dir.join(MOD_FILELIST_FILE),
+            r#"<contentpackage name="Test">
+  <Item file="%ModDir%/Items/good.xml" />
+  <Item file="%ModDir%/Items/case.xml" />
+  <Item file="%ModDir%/Items/bad.xml" />
+  <Item file="%ModDir%/Items/missing.xml" />
+</contentpackage>"#,
+        )
+        .unwrap();
+
+        let mut mod_obj = BarotraumaMod::default();
+        mod_obj.set_home_dir(mod_dir.to_string_lossy().to_string());
+        let validation = mod_obj.validate(game.path(), |_| None).unwrap();
+
+        assert_eq!(validation.files_checked, 4);
+        assert_eq!(validation.health_score, 25);
+        let issues: Vec<&FileIssue> = validation.diagnostics.iter().map(|d| &d.issue).collect();
+        assert_eq!(
+            issues[0],
+            &FileIssue::CaseMismatch {
+                actual: mod_dir.join("Items").join("Case.xml")
+            }
+        );
+        assert!(matches!(issues[1], FileIssue::MalformedXml { line: 2, .. }));
+        assert_eq!(issues[2], &FileIssue::Missing);
+    }
+}
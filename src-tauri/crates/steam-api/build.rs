@@ -7,6 +7,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .add_type_global_attr(
             "#[derive(serde::Deserialize, serde::Serialize)]\n#[serde(rename_all = \"camelCase\")]"
         )
+        .add_type_attr("WorkshopItem", "#[serde(default)]")
         .add_field_attr(
             "WorkshopItem.published_file_id",
             "#[serde(alias = \"publishedfileid\",deserialize_with = \"crate::de::deserialize_u64\")]"
@@ -17,11 +18,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .add_field_attr(
             "WorkshopItem.creator_app_id",
-            "#[serde(alias = \"creator_app_id\")]",
+            "#[serde(alias = \"creator_app_id\", alias = \"creator_appid\")]",
         )
         .add_field_attr(
             "WorkshopItem.consumer_app_id",
-            "#[serde(alias = \"consumer_app_id\")]",
+            "#[serde(alias = \"consumer_app_id\", alias = \"consumer_appid\")]",
         )
         .add_field_attr(
             "WorkshopItem.file_size",
@@ -52,6 +53,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             "#[serde(alias = \"time_created\")]",
         )
 
+        .add_field_attr(
+            "WorkshopItem.description",
+            "#[serde(alias = \"file_description\")]",
+        )
         .add_field_attr(
             "WorkshopItem.ban_reason",
             "#[serde(alias = \"ban_reason\")]",
@@ -0,0 +1,458 @@
+use crate::{Error, ItemOutcome, SteamWorkShopClient, WorkshopItem};
+use logger::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// When [`MetadataCache`] goes to the network.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CacheMode {
+    /// Entries older than the TTL are fetched again before returning.
+    #[default]
+    Refresh,
+    /// Entries older than the TTL are returned right away and refreshed in the background.
+    StaleWhileRevalidate,
+    /// Never go to the network, only cached entries are returned.
+    Offline,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+    /// How long an entry counts as fresh.
+    pub ttl: Duration,
+    pub mode: CacheMode,
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        CachePolicy {
+            ttl: Duration::from_secs(6 * 60 * 60),
+            mode: CacheMode::default(),
+        }
+    }
+}
+
+/// Where a [`Cached`] value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Source {
+    /// Fetched from Steam by this call.
+    Network,
+    /// Read from the cache within the TTL.
+    Cache,
+    /// Read from the cache after the TTL ran out, because the mode allows it or Steam
+    /// could not be reached.
+    Stale,
+}
+
+/// A value with its provenance.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cached<T> {
+    pub value: T,
+    pub source: Source,
+    /// Unix timestamp of when the value was fetched from Steam.
+    pub fetched_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Entry {
+    outcome: ItemOutcome,
+    fetched_at: u64,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    policy: CachePolicy,
+    entries: HashMap<u64, Entry>,
+    /// Bumped on every change to `entries`.
+    generation: u64,
+}
+
+/// Persistent cache of workshop item lookups, keyed by `published_file_id`.
+///
+/// The whole cache is kept in memory and written back as JSON after every fetch.
+/// Writes go through a temporary file and are serialized, so a snapshot never
+/// replaces a newer one on disk.
+#[derive(Debug)]
+pub struct MetadataCache {
+    path: PathBuf,
+    inner: Mutex<Inner>,
+    /// Generation of the snapshot on disk, held while writing.
+    written: Arc<Mutex<u64>>,
+}
+
+impl MetadataCache {
+    /// Loads the cache stored at `path`; a missing or unreadable file starts an empty cache.
+    pub fn open(path: PathBuf, policy: CachePolicy) -> MetadataCache {
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                warn!(
+                    "Discarding workshop metadata cache {}: {}",
+                    path.display(),
+                    e
+                );
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        MetadataCache {
+            path,
+            inner: Mutex::new(Inner {
+                policy,
+                entries,
+                generation: 0,
+            }),
+            written: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn policy(&self) -> CachePolicy {
+        self.lock().policy
+    }
+
+    pub fn set_policy(&self, policy: CachePolicy) {
+        self.lock().policy = policy;
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every entry, in memory and on disk.
+    pub fn clear(&self) -> std::io::Result<()> {
+        let generation = {
+            let mut inner = self.lock();
+            inner.entries.clear();
+            inner.generation += 1;
+            inner.generation
+        };
+        let mut written = self.written.lock().unwrap_or_else(|e| e.into_inner());
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => {
+                *written = generation;
+                Ok(())
+            }
+        }
+    }
+
+    fn get(&self, item_id: u64) -> Option<Cached<ItemOutcome>> {
+        let inner = self.lock();
+        let entry = inner.entries.get(&item_id)?;
+        let age = now().saturating_sub(entry.fetched_at);
+        Some(Cached {
+            value: entry.outcome.clone(),
+            source: if age < inner.policy.ttl.as_secs() {
+                Source::Cache
+            } else {
+                Source::Stale
+            },
+            fetched_at: entry.fetched_at,
+        })
+    }
+
+    async fn store(&self, outcomes: &[ItemOutcome], fetched_at: u64) {
+        let (json, generation) = {
+            let mut inner = self.lock();
+            for outcome in outcomes {
+                let entry = Entry {
+                    outcome: outcome.clone(),
+                    fetched_at,
+                };
+                inner.entries.insert(outcome.item_id(), entry);
+            }
+            inner.generation += 1;
+            (serde_json::to_string(&inner.entries), inner.generation)
+        };
+        let path = self.path.clone();
+        let written = Arc::clone(&self.written);
+        let result = tokio::task::spawn_blocking(move || {
+            let mut written = written.lock().unwrap_or_else(|e| e.into_inner());
+            // A store that snapshotted later got here first
+            if *written >= generation {
+                return Ok(());
+            }
+            write_atomically(&path, json.map_err(std::io::Error::other)?)?;
+            *written = generation;
+            Ok(())
+        })
+        .await
+        .unwrap_or_else(|e| Err(std::io::Error::other(e)));
+        if let Err(e) = result {
+            warn!(
+                "Failed to write workshop metadata cache {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Writes `contents` next to `path` and renames it into place.
+fn write_atomically(path: &Path, contents: String) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, contents)?;
+    std::fs::rename(&temp, path)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+impl SteamWorkShopClient {
+    /// Fetches outcomes from Steam and records them in the cache, if there is one.
+    async fn fetch_and_store(
+        &self,
+        item_ids: Vec<u64>,
+        batch_size: usize,
+    ) -> Result<Vec<Cached<ItemOutcome>>, Error> {
+        let outcomes = self.get_item_outcomes_batched(item_ids, batch_size).await?;
+        let fetched_at = now();
+        if let Some(cache) = &self.cache {
+            cache.store(&outcomes, fetched_at).await;
+        }
+        Ok(outcomes
+            .into_iter()
+            .map(|value| Cached {
+                value,
+                source: Source::Network,
+                fetched_at,
+            })
+            .collect())
+    }
+
+    /// [`SteamWorkShopClient::get_item_outcomes_batched`] through the metadata cache.
+    ///
+    /// Results keep the order of `item_ids`. Without a cache every item is fetched. In
+    /// [`CacheMode::Offline`] items that were never cached are left out, and when Steam
+    /// cannot be reached the cached entries are returned as [`Source::Stale`] instead of
+    /// failing, as long as there are any.
+    pub async fn get_item_outcomes_cached(
+        &self,
+        item_ids: Vec<u64>,
+        batch_size: usize,
+    ) -> Result<Vec<Cached<ItemOutcome>>, Error> {
+        let Some(cache) = self.cache.clone() else {
+            return self.fetch_and_store(item_ids, batch_size).await;
+        };
+        let policy = cache.policy();
+
+        let mut found: HashMap<u64, Cached<ItemOutcome>> = HashMap::new();
+        let mut to_fetch = Vec::new();
+        let mut to_revalidate = Vec::new();
+        for &item_id in &item_ids {
+            match cache.get(item_id) {
+                Some(cached) if cached.source == Source::Cache => {
+                    found.insert(item_id, cached);
+                }
+                Some(stale) => {
+                    match policy.mode {
+                        CacheMode::Refresh => to_fetch.push(item_id),
+                        CacheMode::StaleWhileRevalidate => to_revalidate.push(item_id),
+                        CacheMode::Offline => {}
+                    }
+                    found.insert(item_id, stale);
+                }
+                None if policy.mode == CacheMode::Offline => {}
+                None => to_fetch.push(item_id),
+            }
+        }
+
+        if !to_revalidate.is_empty() {
+            let client = self.clone();
+            tokio::spawn(async move {
+                let count = to_revalidate.len();
+                match client.fetch_and_store(to_revalidate, batch_size).await {
+                    Ok(_) => info!("Revalidated {} cached workshop items", count),
+                    Err(e) => warn!("Failed to revalidate cached workshop items: {}", e),
+                }
+            });
+        }
+
+        if !to_fetch.is_empty() {
+            match self.fetch_and_store(to_fetch, batch_size).await {
+                Ok(fetched) => found.extend(
+                    fetched
+                        .into_iter()
+                        .map(|cached| (cached.value.item_id(), cached)),
+                ),
+                Err(e) if !found.is_empty() => {
+                    warn!("{}, serving workshop metadata from the cache.", e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(item_ids
+            .iter()
+            .filter_map(|item_id| found.remove(item_id))
+            .collect())
+    }
+
+    /// [`SteamWorkShopClient::get_items_batched`] through the metadata cache, see
+    /// [`SteamWorkShopClient::get_item_outcomes_cached`].
+    pub async fn get_items_cached(
+        &self,
+        item_ids: Vec<u64>,
+        batch_size: usize,
+    ) -> Result<Vec<Cached<WorkshopItem>>, Error> {
+        Ok(self
+            .get_item_outcomes_cached(item_ids, batch_size)
+            .await?
+            .into_iter()
+            .filter_map(|cached| {
+                let source = cached.source;
+                let fetched_at = cached.fetched_at;
+                cached.value.into_item().map(|value| Cached {
+                    value,
+                    source,
+                    fetched_at,
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RequestPolicy;
+    use mockito::Server;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    const PATH: &str = "/ISteamRemoteStorage/GetPublishedFileDetails/v1/";
+
+    fn body(titles: &[(u64, &str)]) -> String {
+        json!({
+            "response": {
+                "result": 1,
+                "resultcount": titles.len(),
+                "publishedfiledetails": titles
+                    .iter()
+                    .map(|(id, title)| json!({ "publishedfileid": id.to_string(), "result": 1, "title": title }))
+                    .collect::<Vec<_>>()
+            }
+        })
+        .to_string()
+    }
+
+    fn cached_client(server: &Server, dir: &TempDir, policy: CachePolicy) -> SteamWorkShopClient {
+        let cache = MetadataCache::open(dir.path().join("workshop.json"), policy);
+        SteamWorkShopClient::from_endpoint(format!("{}{PATH}", server.url()))
+            .with_cache(Arc::new(cache))
+    }
+
+    #[tokio::test]
+    async fn test_second_lookup_is_served_from_cache() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", PATH)
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(body(&[(1, "One")]))
+            .expect(1)
+            .create();
+        let dir = TempDir::new().unwrap();
+
+        let client = cached_client(&server, &dir, CachePolicy::default());
+        let first = client.get_items_cached(vec![1], 10).await.unwrap();
+        assert_eq!(first[0].source, Source::Network);
+
+        // A new cache on the same file sees what the first one persisted.
+        let client = cached_client(&server, &dir, CachePolicy::default());
+        let second = client.get_items_cached(vec![1], 10).await.unwrap();
+        assert_eq!(second[0].source, Source::Cache);
+        assert_eq!(second[0].value.title, "One");
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_stale_entries_survive_network_failure() {
+        let mut server = Server::new_async().await;
+        let ok = server
+            .mock("POST", PATH)
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(body(&[(1, "One")]))
+            .create();
+        let dir = TempDir::new().unwrap();
+        let expired = CachePolicy {
+            ttl: Duration::ZERO,
+            mode: CacheMode::Refresh,
+        };
+        let client = cached_client(&server, &dir, expired).with_request_policy(RequestPolicy {
+            max_retries: 0,
+            ..RequestPolicy::default()
+        });
+        client.get_items_cached(vec![1], 10).await.unwrap();
+        ok.remove();
+
+        server.mock("POST", PATH).with_status(500).create();
+        let items = client.get_items_cached(vec![1, 2], 10).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].source, Source::Stale);
+        assert_eq!(items[0].value.title, "One");
+    }
+
+    #[tokio::test]
+    async fn test_offline_mode_never_fetches() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", PATH)
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(body(&[(1, "One")]))
+            .expect(1)
+            .create();
+        let dir = TempDir::new().unwrap();
+        let client = cached_client(&server, &dir, CachePolicy::default());
+        client.get_items_cached(vec![1], 10).await.unwrap();
+
+        client.cache.as_ref().unwrap().set_policy(CachePolicy {
+            ttl: Duration::ZERO,
+            mode: CacheMode::Offline,
+        });
+        let items = client.get_items_cached(vec![1, 2], 10).await.unwrap();
+        let sources: Vec<_> = items
+            .iter()
+            .map(|c| (c.value.published_file_id, c.source))
+            .collect();
+        assert_eq!(sources, vec![(1, Source::Stale)]);
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_stores_keep_newest_snapshot() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("workshop.json");
+        let cache = MetadataCache::open(path.clone(), CachePolicy::default());
+        let outcome = |item_id| ItemOutcome::NotFound { item_id, result: 9 };
+
+        let outcomes: Vec<_> = (1..=3).map(outcome).collect();
+        futures::future::join_all(outcomes.chunks(1).map(|chunk| cache.store(chunk, 1))).await;
+        let reopened = MetadataCache::open(path.clone(), CachePolicy::default());
+        assert_eq!(reopened.len(), 3);
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+}
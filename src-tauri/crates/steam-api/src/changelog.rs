@@ -0,0 +1,240 @@
+use crate::{Error, SteamWorkShopClient, request};
+use serde::{Deserialize, Serialize};
+
+/// One entry of a Workshop item's change notes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeNote {
+    /// Unix timestamp of the update the note belongs to.
+    pub timestamp: u64,
+    /// The note as plain text, lines separated by `\n`. Empty when the author left none.
+    pub text: String,
+}
+
+/// Marks each entry of the changelog page, followed by `<p id="<timestamp>">`.
+const HEADLINE: &str = r#"<div class="changelog headline">"#;
+
+/// Parses the change notes of a Workshop changelog page, as served at
+/// `https://steamcommunity.com/sharedfiles/filedetails/changelog/<id>`.
+///
+/// Notes are returned in page order, which is newest first. Line breaks are kept,
+/// other markup such as links and BBCode formatting is dropped.
+pub fn parse_change_notes(html: &str) -> Vec<ChangeNote> {
+    html.split(HEADLINE)
+        .skip(1)
+        .filter_map(|entry| {
+            let (_, rest) = entry.split_once("<p id=\"")?;
+            let (id, rest) = rest.split_once('"')?;
+            let timestamp = id.parse().ok()?;
+            let (_, rest) = rest.split_once('>')?;
+            let (body, _) = rest.split_once("</p>")?;
+            Some(ChangeNote {
+                timestamp,
+                text: html_to_text(body),
+            })
+        })
+        .collect()
+}
+
+/// Converts the HTML of one note to plain text.
+fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        let name = tag.trim_start_matches('/').split([' ', '/']).next();
+        if name.is_some_and(|name| name.eq_ignore_ascii_case("br")) {
+            text.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+
+    let text = unescape(&text);
+    text.lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Decodes the named entities Steam emits and numeric character references.
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').and_then(|end| {
+            let character = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => ' ',
+                entity => {
+                    let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => entity.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((character, end))
+        });
+        match decoded {
+            Some((character, end)) => {
+                unescaped.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+impl SteamWorkShopClient {
+    /// Most changelog pages [`SteamWorkShopClient::get_change_notes`] walks through.
+    pub const MAX_CHANGELOG_PAGES: u32 = 10;
+
+    /// Fetch the change notes of a Workshop item from its changelog page, newest first.
+    ///
+    /// With `since`, usually when the installed copy was downloaded, only notes newer
+    /// than it are returned and paging stops once an older one is seen. Otherwise up to
+    /// [`SteamWorkShopClient::MAX_CHANGELOG_PAGES`] pages are read.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use steam_api::SteamWorkShopClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = SteamWorkShopClient::new();
+    /// for note in client.get_change_notes(3354525188, Some(1704067200)).await? {
+    ///     println!("{}: {}", note.timestamp, note.text);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_change_notes(
+        &self,
+        item_id: u64,
+        since: Option<u64>,
+    ) -> Result<Vec<ChangeNote>, Error> {
+        let url = format!("{}{}", self.changelog_endpoint, item_id);
+        let mut notes: Vec<ChangeNote> = Vec::new();
+
+        for page in 1..=Self::MAX_CHANGELOG_PAGES {
+            let html = request::send(self.policy.get(), || {
+                self.client
+                    .get(url.as_str())
+                    .query(&[("p", page.to_string().as_str()), ("l", "english")])
+            })
+            .await?
+            .text()
+            .await?;
+
+            let page_notes = parse_change_notes(&html);
+            // Steam answers pages past the end with the last one again
+            let repeated = page_notes
+                .first()
+                .is_some_and(|first| notes.iter().any(|note| note == first));
+            if page_notes.is_empty() || repeated {
+                break;
+            }
+
+            let mut reached_since = false;
+            for note in page_notes {
+                if since.is_some_and(|since| note.timestamp <= since) {
+                    reached_since = true;
+                    break;
+                }
+                notes.push(note);
+            }
+            if reached_since {
+                break;
+            }
+        }
+        Ok(notes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+
+    const CHANGELOG: &str = include_str!("../fixtures/changelog.html");
+    const PATH: &str = "/sharedfiles/filedetails/changelog/";
+
+    #[test]
+    fn test_parse_change_notes() {
+        let notes = parse_change_notes(CHANGELOG);
+        assert_eq!(
+            notes,
+            vec![
+                ChangeNote {
+                    timestamp: 1710255840,
+                    text: "Fixed crash when docking & undocking\nRebalanced \"Heavy\" ammo\n- Added 3 items"
+                        .to_string(),
+                },
+                ChangeNote {
+                    timestamp: 1706868660,
+                    text: "Big update\nPatch notes 'here'".to_string(),
+                },
+                ChangeNote {
+                    timestamp: 1704484800,
+                    text: String::new(),
+                },
+            ]
+        );
+        assert!(parse_change_notes("<html></html>").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_change_notes_since() {
+        let mut server = Server::new_async().await;
+        let first = server
+            .mock("GET", format!("{PATH}42").as_str())
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("p".into(), "1".into()),
+                Matcher::UrlEncoded("l".into(), "english".into()),
+            ]))
+            .with_status(200)
+            .with_header("Content-Type", "text/html; charset=UTF-8")
+            .with_body(CHANGELOG)
+            .expect(2)
+            .create();
+        let second = server
+            .mock("GET", format!("{PATH}42").as_str())
+            .match_query(Matcher::UrlEncoded("p".into(), "2".into()))
+            .with_status(200)
+            .with_body(CHANGELOG)
+            .create();
+
+        let client =
+            SteamWorkShopClient::new().with_changelog_endpoint(format!("{}{PATH}", server.url()));
+        let notes = client.get_change_notes(42, Some(1706868660)).await.unwrap();
+        // The installed version was reached on the first page
+        assert!(!second.matched());
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].timestamp, 1710255840);
+
+        // Without `since` paging stops when Steam repeats the last page
+        let notes = client.get_change_notes(42, None).await.unwrap();
+        first.assert();
+        second.assert();
+        assert_eq!(notes.len(), 3);
+    }
+}
@@ -0,0 +1,369 @@
+use crate::{Error, ItemOutcome, SteamWorkShopClient};
+use futures::{StreamExt, TryStreamExt, stream};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::iter::once;
+
+/// One entry of a Workshop collection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionChild {
+    #[serde(
+        alias = "publishedfileid",
+        deserialize_with = "crate::de::deserialize_u64"
+    )]
+    pub item_id: u64,
+    #[serde(alias = "sortorder", default)]
+    pub sort_order: i64,
+    /// `EWorkshopFileType`, [`CollectionChild::FILE_TYPE_COLLECTION`] for nested collections.
+    #[serde(alias = "filetype", default)]
+    pub file_type: u32,
+}
+
+impl CollectionChild {
+    pub const FILE_TYPE_COLLECTION: u32 = 2;
+
+    pub fn is_collection(&self) -> bool {
+        self.file_type == Self::FILE_TYPE_COLLECTION
+    }
+}
+
+/// What Steam returned for one requested collection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionDetails {
+    #[serde(
+        alias = "publishedfileid",
+        deserialize_with = "crate::de::deserialize_u64"
+    )]
+    pub collection_id: u64,
+    /// Steam result code, 1 when the collection was found.
+    pub result: i32,
+    /// Direct children ordered by their sort order.
+    #[serde(default)]
+    pub children: Vec<CollectionChild>,
+}
+
+#[derive(Deserialize)]
+struct CollectionResponse {
+    response: CollectionResult,
+}
+
+#[derive(Deserialize)]
+struct CollectionResult {
+    result: isize,
+    #[serde(default, rename = "collectiondetails")]
+    collection_details: Vec<CollectionDetails>,
+}
+
+impl SteamWorkShopClient {
+    /// Fetch the direct children of Workshop collections through
+    /// `ISteamRemoteStorage/GetCollectionDetails/v1/`.
+    ///
+    /// Returns one [`CollectionDetails`] per collection Steam answered for; IDs that are
+    /// not collections come back without children.
+    pub async fn get_collection_details(
+        &self,
+        collection_ids: Vec<u64>,
+    ) -> Result<Vec<CollectionDetails>, Error> {
+        if collection_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let form_data = once((
+            "collectioncount".to_string(),
+            Value::from(collection_ids.len()),
+        ))
+        .chain(
+            collection_ids
+                .iter()
+                .enumerate()
+                .map(|(idx, &id)| (format!("publishedfileids[{}]", idx), Value::from(id))),
+        )
+        .collect::<Map<String, Value>>();
+
+        let response: CollectionResponse = crate::request::send(self.policy.get(), || {
+            self.client
+                .post(self.collection_endpoint.as_str())
+                .form(&form_data)
+        })
+        .await?
+        .json()
+        .await?;
+
+        if response.response.result != 1 {
+            return Err(Error::ApiFailure(response.response.result));
+        }
+
+        let mut details = response.response.collection_details;
+        details
+            .iter_mut()
+            .for_each(|collection| collection.children.sort_by_key(|child| child.sort_order));
+        Ok(details)
+    }
+
+    /// Fetch the "Required Items" Workshop authors declared for their items.
+    ///
+    /// `GetCollectionDetails` lists the required items of an ordinary item as its
+    /// children, so unlike `IPublishedFileService/GetDetails` this needs no API key.
+    /// Items without required items map to an empty list, items Steam did not find are
+    /// left out. `batch_size` 0 sends a single request.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use steam_api::SteamWorkShopClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = SteamWorkShopClient::new();
+    /// let required = client.get_required_items(vec![3354525188], 50).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn get_required_items(
+        &self,
+        item_ids: Vec<u64>,
+        batch_size: usize,
+    ) -> Result<HashMap<u64, Vec<u64>>, Error> {
+        let batch_size = if batch_size == 0 {
+            item_ids.len().max(1)
+        } else {
+            batch_size
+        };
+        let batches: Vec<Vec<u64>> = item_ids.chunks(batch_size).map(<[u64]>::to_vec).collect();
+        let results: Vec<Vec<CollectionDetails>> = stream::iter(batches)
+            .map(|batch| self.get_collection_details(batch))
+            .buffered(self.policy.get().concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        Ok(results
+            .into_iter()
+            .flatten()
+            .filter(|details| details.result == 1)
+            .map(|details| {
+                let required = details.children.iter().map(|child| child.item_id).collect();
+                (details.collection_id, required)
+            })
+            .collect())
+    }
+
+    /// Every item of a collection in the collection's order.
+    ///
+    /// Nested collections are fetched and expanded where they appear; items that show up
+    /// more than once are kept at their first position.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ItemUnavailable`] when `collection_id` itself cannot be fetched.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use steam_api::SteamWorkShopClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = SteamWorkShopClient::new();
+    /// let mods = client.get_collection_items(2961925632).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn get_collection_items(&self, collection_id: u64) -> Result<Vec<u64>, Error> {
+        let mut collections: HashMap<u64, Vec<CollectionChild>> = HashMap::new();
+        let mut requested = HashSet::from([collection_id]);
+        let mut pending = vec![collection_id];
+
+        while !pending.is_empty() {
+            for details in self.get_collection_details(pending).await? {
+                if details.collection_id == collection_id && details.result != 1 {
+                    return Err(Error::ItemUnavailable {
+                        item_id: collection_id,
+                        result: details.result,
+                    });
+                }
+                if details.result == 1 {
+                    collections.insert(details.collection_id, details.children);
+                }
+            }
+            pending = collections
+                .values()
+                .flatten()
+                .filter(|child| child.is_collection() && requested.insert(child.item_id))
+                .map(|child| child.item_id)
+                .collect();
+        }
+
+        if !collections.contains_key(&collection_id) {
+            return Err(Error::ItemUnavailable {
+                item_id: collection_id,
+                result: ItemOutcome::RESULT_FILE_NOT_FOUND,
+            });
+        }
+        Ok(flatten(collection_id, &collections))
+    }
+}
+
+/// Expands `root` depth-first, skipping collections already being expanded and repeated items.
+fn flatten(root: u64, collections: &HashMap<u64, Vec<CollectionChild>>) -> Vec<u64> {
+    fn visit(
+        collection_id: u64,
+        collections: &HashMap<u64, Vec<CollectionChild>>,
+        visited: &mut HashSet<u64>,
+        items: &mut Vec<u64>,
+    ) {
+        if !visited.insert(collection_id) {
+            return;
+        }
+        for child in collections.get(&collection_id).into_iter().flatten() {
+            if child.is_collection() {
+                visit(child.item_id, collections, visited, items);
+            } else if !items.contains(&child.item_id) {
+                items.push(child.item_id);
+            }
+        }
+    }
+
+    let mut items = Vec::new();
+    visit(root, collections, &mut HashSet::new(), &mut items);
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+
+    const PATH: &str = "/ISteamRemoteStorage/GetCollectionDetails/v1/";
+
+    fn child(id: u64, sort_order: i64, file_type: u32) -> Value {
+        json!({ "publishedfileid": id.to_string(), "sortorder": sort_order, "filetype": file_type })
+    }
+
+    #[tokio::test]
+    async fn test_nested_collection_items() {
+        let mut server = Server::new_async().await;
+        let root = server
+            .mock("POST", PATH)
+            .match_body(Matcher::UrlEncoded(
+                "publishedfileids[0]".into(),
+                "100".into(),
+            ))
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(
+                json!({
+                    "response": {
+                        "result": 1,
+                        "resultcount": 1,
+                        "collectiondetails": [{
+                            "publishedfileid": "100",
+                            "result": 1,
+                            "children": [child(3, 3, 0), child(1, 0, 0), child(200, 1, 2), child(2, 2, 0)]
+                        }]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+        let nested = server
+            .mock("POST", PATH)
+            .match_body(Matcher::UrlEncoded(
+                "publishedfileids[0]".into(),
+                "200".into(),
+            ))
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(
+                json!({
+                    "response": {
+                        "result": 1,
+                        "resultcount": 1,
+                        "collectiondetails": [{
+                            "publishedfileid": "200",
+                            "result": 1,
+                            // Refers back to the root and repeats an item of it
+                            "children": [child(10, 0, 0), child(100, 1, 2), child(2, 2, 0)]
+                        }]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client =
+            SteamWorkShopClient::new().with_collection_endpoint(format!("{}{PATH}", server.url()));
+        let items = client.get_collection_items(100).await.unwrap();
+
+        root.assert();
+        nested.assert();
+        assert_eq!(items, vec![1, 10, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_required_items() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", PATH)
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(
+                json!({
+                    "response": {
+                        "result": 1,
+                        "resultcount": 3,
+                        "collectiondetails": [
+                            {
+                                "publishedfileid": "1",
+                                "result": 1,
+                                "children": [child(20, 1, 0), child(10, 0, 0)]
+                            },
+                            { "publishedfileid": "2", "result": 1 },
+                            { "publishedfileid": "3", "result": 9 }
+                        ]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client =
+            SteamWorkShopClient::new().with_collection_endpoint(format!("{}{PATH}", server.url()));
+        let required = client.get_required_items(vec![1, 2, 3], 0).await.unwrap();
+
+        assert_eq!(required.len(), 2);
+        assert_eq!(required[&1], vec![10, 20]);
+        assert!(required[&2].is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_missing_collection() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", PATH)
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(
+                json!({
+                    "response": {
+                        "result": 1,
+                        "resultcount": 1,
+                        "collectiondetails": [{ "publishedfileid": "100", "result": 9 }]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client =
+            SteamWorkShopClient::new().with_collection_endpoint(format!("{}{PATH}", server.url()));
+        let result = client.get_collection_items(100).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::ItemUnavailable {
+                item_id: 100,
+                result: 9
+            })
+        ));
+    }
+}
@@ -134,3 +134,42 @@ where
 
     deserializer.deserialize_any(U64Visitor)
 }
+
+/// Optional variant of [`deserialize_u64`]. `null`, empty strings and 0 are `None`.
+pub fn deserialize_optional_u64<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct OptionVisitor;
+
+    impl<'de> Visitor<'de> for OptionVisitor {
+        type Value = Option<u64>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("null, a string or number")
+        }
+
+        fn visit_none<E>(self) -> Result<Option<u64>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_unit<E>(self) -> Result<Option<u64>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Option<u64>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserialize_u64(deserializer).map(|v| Some(v).filter(|&v| v > 0))
+        }
+    }
+
+    deserializer.deserialize_option(OptionVisitor)
+}
@@ -5,9 +5,18 @@
 //!
 //! This endpoint **requires HTTP POST**.
 //!
+//! Lookups can be cached on disk with [`MetadataCache`], see
+//! [`SteamWorkShopClient::get_item_outcomes_cached`].
+//!
+//! Browsing and searching goes through [`QueryFilesClient`], which wraps
+//! `IPublishedFileService/QueryFiles/v1/` and needs a Steam Web API key.
+//! [`PlayerSummariesClient`] resolves Workshop creators to their Steam profiles
+//! and needs one as well.
+//!
 //! ## Features
 //! - Uses `u64` for item IDs (correct type for Steam's PublishedFileID)
 //! - Fetch single or multiple items
+//! - Bounded concurrency, timeouts and retries, see [`RequestPolicy`]
 //! - Proper error handling
 //! - No unnecessary string allocations
 //!
@@ -34,15 +43,32 @@
 //! }
 //! ```
 
+mod cache;
+mod changelog;
+mod collection;
 pub mod de;
+mod player;
+mod query;
+mod request;
+mod validate;
 mod workshop;
 
+pub use crate::cache::{CacheMode, CachePolicy, Cached, MetadataCache, Source};
+pub use crate::changelog::{ChangeNote, parse_change_notes};
+pub use crate::collection::{CollectionChild, CollectionDetails};
+pub use crate::player::{PlayerSummariesClient, PlayerSummary};
+pub use crate::query::{QueryFilesClient, QueryPage, Ranking, WorkshopQuery};
+pub use crate::request::{RequestPolicy, SharedRequestPolicy};
+pub use crate::validate::{Availability, ItemValidation, ValidationIssue};
 pub use crate::workshop::*;
-use futures::future::try_join_all;
+use futures::{StreamExt, TryStreamExt, stream};
 use logger::info;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::iter::once;
+use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Main client for querying Steam Workshop API.
@@ -50,6 +76,10 @@ use thiserror::Error;
 pub struct SteamWorkShopClient {
     client: reqwest::Client,
     endpoint: String,
+    collection_endpoint: String,
+    changelog_endpoint: String,
+    cache: Option<Arc<MetadataCache>>,
+    policy: SharedRequestPolicy,
 }
 
 impl Default for SteamWorkShopClient {
@@ -58,6 +88,10 @@ impl Default for SteamWorkShopClient {
         SteamWorkShopClient {
             client,
             endpoint: Self::DEFAULT_ENDPOINT.to_string(),
+            collection_endpoint: Self::DEFAULT_COLLECTION_ENDPOINT.to_string(),
+            changelog_endpoint: Self::DEFAULT_CHANGELOG_ENDPOINT.to_string(),
+            cache: None,
+            policy: SharedRequestPolicy::default(),
         }
     }
 }
@@ -65,13 +99,48 @@ impl Default for SteamWorkShopClient {
 impl SteamWorkShopClient {
     const DEFAULT_ENDPOINT: &'static str =
         "https://api.steampowered.com/ISteamRemoteStorage/GetPublishedFileDetails/v1/";
+    const DEFAULT_COLLECTION_ENDPOINT: &'static str =
+        "https://api.steampowered.com/ISteamRemoteStorage/GetCollectionDetails/v1/";
+    const DEFAULT_CHANGELOG_ENDPOINT: &'static str =
+        "https://steamcommunity.com/sharedfiles/filedetails/changelog/";
     pub fn new() -> Self {
         Self::default()
     }
     /// Creates a new `WorkshopClient`.
     pub fn from_endpoint(endpoint: String) -> Self {
-        let client = reqwest::Client::new();
-        SteamWorkShopClient { client, endpoint }
+        SteamWorkShopClient {
+            endpoint,
+            ..Self::default()
+        }
+    }
+
+    /// Serves the `*_cached` lookups from `cache`.
+    pub fn with_cache(mut self, cache: Arc<MetadataCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn cache(&self) -> Option<&Arc<MetadataCache>> {
+        self.cache.as_ref()
+    }
+
+    /// Throttles and retries requests according to `policy`, which other clients may
+    /// share.
+    pub fn with_request_policy(mut self, policy: impl Into<SharedRequestPolicy>) -> Self {
+        self.policy = policy.into();
+        self
+    }
+
+    /// Replaces the `GetCollectionDetails` endpoint.
+    pub fn with_collection_endpoint(mut self, endpoint: String) -> Self {
+        self.collection_endpoint = endpoint;
+        self
+    }
+
+    /// Replaces the changelog page URL, the item ID is appended to `endpoint`.
+    pub fn with_changelog_endpoint(mut self, endpoint: String) -> Self {
+        self.changelog_endpoint = endpoint;
+        self
     }
 
     /// Fetch a single Workshop item by its published file ID (u64).
@@ -82,7 +151,8 @@ impl SteamWorkShopClient {
     ///
     /// # Returns
     ///
-    /// Returns `Ok(WorkshopItem)` on success.
+    /// Returns `Ok(WorkshopItem)` on success, or [`Error::ItemUnavailable`] if the item
+    /// was removed, is private or banned.
     ///
     /// # Example
     ///
@@ -95,11 +165,14 @@ impl SteamWorkShopClient {
     /// # Ok(()) }
     /// ```
     pub async fn get_item(&self, item_id: u64) -> Result<WorkshopItem, Error> {
-        self.get_items(vec![item_id])
-            .await?
-            .into_iter()
-            .next()
-            .ok_or(Error::EmptyResponse)
+        match self.get_item_outcomes(vec![item_id]).await?.pop() {
+            Some(ItemOutcome::Found(item)) => Ok(*item),
+            Some(outcome) => Err(Error::ItemUnavailable {
+                item_id,
+                result: outcome.result(),
+            }),
+            None => Err(Error::EmptyResponse),
+        }
     }
 
     /// Fetch multiple Workshop items by their IDs (as `u64`).
@@ -115,6 +188,7 @@ impl SteamWorkShopClient {
     /// Returns a `Vec<WorkshopItem>` of successfully returned items.
     ///
     /// ⚠️ Steam may return fewer items than requested (e.g., deleted, private, or banned items).
+    /// Those are left out, use [`SteamWorkShopClient::get_item_outcomes`] to see why.
     ///
     /// # Example
     ///
@@ -127,6 +201,31 @@ impl SteamWorkShopClient {
     /// # Ok(()) }
     /// ```
     pub async fn get_items(&self, item_ids: Vec<u64>) -> Result<Vec<WorkshopItem>, Error> {
+        Ok(found(self.get_item_outcomes(item_ids).await?))
+    }
+
+    /// Fetch multiple Workshop items by their IDs, with one [`ItemOutcome`] per requested ID.
+    ///
+    /// Maximum: 100 items per request (Steam API limit).
+    ///
+    /// Outcomes are in the order of `item_ids`. Deleted, private and banned items do not fail
+    /// the request; only a failure of the request as a whole returns [`Error::ApiFailure`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use steam_api::{ItemOutcome, SteamWorkShopClient};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = SteamWorkShopClient::new();
+    /// for outcome in client.get_item_outcomes(vec![3354525188, 1234567890]).await? {
+    ///     if let ItemOutcome::NotFound { item_id, .. } = outcome {
+    ///         println!("{item_id} was removed from the workshop");
+    ///     }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn get_item_outcomes(&self, item_ids: Vec<u64>) -> Result<Vec<ItemOutcome>, Error> {
         if item_ids.is_empty() {
             return Ok(vec![]);
         }
@@ -140,34 +239,44 @@ impl SteamWorkShopClient {
             )
             .collect::<Map<String, Value>>();
 
-        let response: ApiResponse = self
-            .client
-            .post(self.endpoint.as_str())
-            .form(&form_data)
-            .send()
-            .await?
-            .json()
-            .await?;
+        let response: ApiResponse = request::send(self.policy.get(), || {
+            self.client.post(self.endpoint.as_str()).form(&form_data)
+        })
+        .await?
+        .json()
+        .await?;
 
-        if response.response.result != 1 || response.response.result_count != item_ids.len() {
+        if response.response.result != 1 {
             return Err(Error::ApiFailure(response.response.result));
         }
 
-        let items = response
+        let mut details = response
             .response
             .published_file_details
             .into_iter()
-            .filter(|item| item.result == 1)
+            .map(|item| (item.published_file_id, item))
+            .collect::<HashMap<u64, WorkshopItem>>();
+
+        let outcomes = item_ids
+            .iter()
+            .map(|&item_id| match details.remove(&item_id) {
+                Some(item) => ItemOutcome::from_details(item),
+                None => ItemOutcome::NotFound {
+                    item_id,
+                    result: ItemOutcome::RESULT_FILE_NOT_FOUND,
+                },
+            })
             .collect();
 
-        Ok(items)
+        Ok(outcomes)
     }
 
     /// Fetch multiple Workshop items by their IDs (as `u64`) in batches.
     ///
     /// This method splits the provided item IDs into batches of the specified size
-    /// and executes the requests concurrently. This is useful when you have more
-    /// than 100 items to fetch, as the Steam API limits each request to 100 items.
+    /// and executes up to [`RequestPolicy::concurrency`] of them at once. This is useful
+    /// when you have more than 100 items to fetch, as the Steam API limits each request
+    /// to 100 items.
     ///
     /// # Arguments
     ///
@@ -180,9 +289,8 @@ impl SteamWorkShopClient {
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - `batch_size` is zero
-    /// - Any individual batch request fails
+    /// Returns an error if any individual batch request fails. Unavailable items
+    /// are skipped, they do not fail their batch.
     ///
     /// # Example
     ///
@@ -199,8 +307,21 @@ impl SteamWorkShopClient {
         item_ids: Vec<u64>,
         batch_size: usize,
     ) -> Result<Vec<WorkshopItem>, Error> {
+        Ok(found(
+            self.get_item_outcomes_batched(item_ids, batch_size).await?,
+        ))
+    }
+
+    /// Batched version of [`SteamWorkShopClient::get_item_outcomes`].
+    ///
+    /// Outcomes keep the order of `item_ids`; `batch_size` 0 sends a single request.
+    pub async fn get_item_outcomes_batched(
+        &self,
+        item_ids: Vec<u64>,
+        batch_size: usize,
+    ) -> Result<Vec<ItemOutcome>, Error> {
         if batch_size == 0 {
-            return self.get_items(item_ids).await;
+            return self.get_item_outcomes(item_ids).await;
         }
 
         if item_ids.is_empty() {
@@ -208,23 +329,42 @@ impl SteamWorkShopClient {
         }
 
         // Split item_ids into multiple batches
-        let batches = item_ids
-            .chunks(batch_size)
-            .map(|chunk| chunk.to_vec())
-            .map(|batch| self.get_items(batch))
-            .collect::<Vec<_>>();
+        let batches: Vec<Vec<u64>> = item_ids.chunks(batch_size).map(<[u64]>::to_vec).collect();
+
+        // Execute a bounded number of batches concurrently, keeping their order
+        let concurrency = self.policy.get().concurrency.max(1);
+        let results: Vec<Vec<ItemOutcome>> = stream::iter(batches)
+            .map(|batch| self.get_item_outcomes(batch))
+            .buffered(concurrency)
+            .try_collect()
+            .await?;
 
-        // Execute all batches concurrently
-        let results: Vec<Vec<WorkshopItem>> = try_join_all(batches).await?;
+        let outcomes: Vec<ItemOutcome> = results.into_iter().flatten().collect();
 
-        let fi: Vec<WorkshopItem> = results.into_iter().flatten().collect();
+        info!(
+            "[{}/{}] items fetched",
+            outcomes.iter().filter(|outcome| outcome.is_found()).count(),
+            item_ids.len()
+        );
 
-        info!("[{}/{}] items fetched", fi.len(), item_ids.len());
+        Ok(outcomes)
+    }
 
-        Ok(fi)
+    /// Download a preview image, such as [`WorkshopItem::preview_url`], from Steam's CDN.
+    pub async fn download_preview(&self, url: &str) -> Result<Vec<u8>, Error> {
+        let response = request::send(self.policy.get(), || self.client.get(url)).await?;
+        Ok(response.bytes().await?.to_vec())
     }
 }
 
+/// Keeps the items that were found, dropping the unavailable ones.
+fn found(outcomes: Vec<ItemOutcome>) -> Vec<WorkshopItem> {
+    outcomes
+        .into_iter()
+        .filter_map(ItemOutcome::into_item)
+        .collect()
+}
+
 // ===================================
 // Data Structures
 // ===================================
@@ -237,12 +377,93 @@ struct ApiResponse {
 #[derive(Deserialize)]
 struct Response {
     result: isize,
-    #[serde(rename = "resultcount")]
-    result_count: usize,
     #[serde(rename = "publishedfiledetails")]
     published_file_details: Vec<WorkshopItem>,
 }
 
+/// What Steam returned for one requested Workshop item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ItemOutcome {
+    /// The item exists and is visible, its `result` is 1.
+    Found(Box<WorkshopItem>),
+    /// The item was deleted, never existed or Steam did not return it.
+    #[serde(rename_all = "camelCase")]
+    NotFound { item_id: u64, result: i32 },
+    /// The item exists but is hidden from the caller.
+    #[serde(rename_all = "camelCase")]
+    Private { item_id: u64, result: i32 },
+    /// Steam refused the item as banned.
+    ///
+    /// Banned items that are still returned with their details are [`ItemOutcome::Found`],
+    /// see [`WorkshopItem::is_banned`].
+    #[serde(rename_all = "camelCase")]
+    Banned {
+        item_id: u64,
+        result: i32,
+        reason: String,
+    },
+}
+
+impl ItemOutcome {
+    /// `k_EResultOK`.
+    pub const RESULT_OK: i32 = 1;
+    /// `k_EResultFileNotFound`, also used for items missing from the response.
+    pub const RESULT_FILE_NOT_FOUND: i32 = 9;
+    /// `k_EResultAccessDenied`, returned for private and friends-only items.
+    pub const RESULT_ACCESS_DENIED: i32 = 15;
+    /// `k_EResultBanned`.
+    pub const RESULT_BANNED: i32 = 17;
+
+    /// Classifies one entry of `publishedfiledetails`.
+    fn from_details(item: WorkshopItem) -> ItemOutcome {
+        let item_id = item.published_file_id;
+        match item.result {
+            Self::RESULT_OK => ItemOutcome::Found(Box::new(item)),
+            Self::RESULT_ACCESS_DENIED => ItemOutcome::Private {
+                item_id,
+                result: item.result,
+            },
+            Self::RESULT_BANNED => ItemOutcome::Banned {
+                item_id,
+                result: item.result,
+                reason: item.ban_reason,
+            },
+            result => ItemOutcome::NotFound { item_id, result },
+        }
+    }
+
+    pub fn item_id(&self) -> u64 {
+        match self {
+            ItemOutcome::Found(item) => item.published_file_id,
+            ItemOutcome::NotFound { item_id, .. }
+            | ItemOutcome::Private { item_id, .. }
+            | ItemOutcome::Banned { item_id, .. } => *item_id,
+        }
+    }
+
+    /// The Steam result code of the item.
+    pub fn result(&self) -> i32 {
+        match self {
+            ItemOutcome::Found(item) => item.result,
+            ItemOutcome::NotFound { result, .. }
+            | ItemOutcome::Private { result, .. }
+            | ItemOutcome::Banned { result, .. } => *result,
+        }
+    }
+
+    pub fn is_found(&self) -> bool {
+        matches!(self, ItemOutcome::Found(_))
+    }
+
+    pub fn into_item(self) -> Option<WorkshopItem> {
+        match self {
+            ItemOutcome::Found(item) => Some(*item),
+            _ => None,
+        }
+    }
+}
+
 // ===================================
 // Error Types
 // ===================================
@@ -257,12 +478,36 @@ pub enum Error {
 
     #[error("no items returned in response")]
     EmptyResponse,
+
+    #[error("a Steam Web API key is required to query the workshop")]
+    MissingApiKey,
+
+    #[error("workshop item {item_id} is unavailable (result code {result})")]
+    ItemUnavailable { item_id: u64, result: i32 },
+
+    /// Steam kept answering 429 Too Many Requests, `retry_after` is its last `Retry-After`.
+    #[error("rate limited by the Steam Web API")]
+    RateLimited { retry_after: Option<Duration> },
 }
 
 // ===================================
 // Convenience Methods
 // ===================================
 
+/// Extracts a Workshop item ID from a bare ID or a Workshop URL such as
+/// `https://steamcommunity.com/sharedfiles/filedetails/?id=123456789`.
+pub fn parse_item_id(input: &str) -> Option<u64> {
+    let input = input.trim();
+    if let Ok(id) = input.parse::<u64>() {
+        return Some(id);
+    }
+    let (_, query) = input.split_once('?')?;
+    query
+        .split(['&', '#'])
+        .find_map(|pair| pair.strip_prefix("id="))
+        .and_then(|id| id.parse::<u64>().ok())
+}
+
 impl WorkshopItem {
     /// Returns trimmed preview URL.
     pub fn preview_url(&self) -> &str {
@@ -501,8 +746,106 @@ mod tests {
         let result = client.get_item(123).await;
 
         // Assert
-        assert!(result.is_err());
-        assert!(matches!(result, Err(Error::ApiFailure(1))));
+        assert!(matches!(
+            result,
+            Err(Error::ItemUnavailable {
+                item_id: 123,
+                result: 9
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_items_partial_results() {
+        // Arrange: one item is available, the others were removed, hidden or banned
+        let response_json = json!({
+            "response": {
+                "result": 1,
+                "resultcount": 4,
+                "publishedfiledetails": [
+                    { "publishedfileid": "4", "result": 17, "ban_reason": "Spam" },
+                    { "publishedfileid": "1", "result": 1, "title": "Still Here" },
+                    { "publishedfileid": "2", "result": 9 },
+                    { "publishedfileid": "3", "result": 15 }
+                ]
+            }
+        });
+
+        let mut server = Server::new_async().await;
+        let url = setup_mock_response(&mut server, response_json).await;
+        let client = SteamWorkShopClient::from_endpoint(url);
+
+        // Act
+        let outcomes = client
+            .get_item_outcomes(vec![1, 2, 3, 4, 5])
+            .await
+            .expect("Partial results should not fail the batch");
+
+        // Assert: outcomes follow the requested order, missing IDs count as not found
+        let ids: Vec<u64> = outcomes.iter().map(ItemOutcome::item_id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+        assert!(matches!(&outcomes[0], ItemOutcome::Found(item) if item.title == "Still Here"));
+        assert_eq!(
+            outcomes[1],
+            ItemOutcome::NotFound {
+                item_id: 2,
+                result: 9
+            }
+        );
+        assert_eq!(
+            outcomes[2],
+            ItemOutcome::Private {
+                item_id: 3,
+                result: 15
+            }
+        );
+        assert_eq!(
+            outcomes[3],
+            ItemOutcome::Banned {
+                item_id: 4,
+                result: 17,
+                reason: "Spam".to_string()
+            }
+        );
+        assert_eq!(outcomes[4].result(), ItemOutcome::RESULT_FILE_NOT_FOUND);
+
+        let items = client.get_items(vec![1, 2, 3, 4, 5]).await.unwrap();
+        assert_eq!(items.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_download_preview() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/preview.jpg")
+            .with_status(200)
+            .with_body([1u8, 2, 3])
+            .create();
+
+        let preview = SteamWorkShopClient::new()
+            .download_preview(&format!("{}/preview.jpg", server.url()))
+            .await
+            .unwrap();
+
+        mock.assert();
+        assert_eq!(preview, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_parse_item_id() {
+        assert_eq!(parse_item_id(" 3354525188 "), Some(3354525188));
+        assert_eq!(
+            parse_item_id("https://steamcommunity.com/sharedfiles/filedetails/?id=3354525188"),
+            Some(3354525188)
+        );
+        assert_eq!(
+            parse_item_id(
+                "https://steamcommunity.com/workshop/filedetails/?l=english&id=123&searchtext="
+            ),
+            Some(123)
+        );
+        assert_eq!(parse_item_id("https://steamcommunity.com/app/602960"), None);
+        assert_eq!(parse_item_id("not an id"), None);
     }
 
     #[tokio::test]
@@ -0,0 +1,256 @@
+use crate::Error;
+use crate::request::{self, SharedRequestPolicy};
+use futures::{StreamExt, TryStreamExt, stream};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Public profile of a Steam user, as returned by `ISteamUser/GetPlayerSummaries/v2/`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerSummary {
+    #[serde(alias = "steamid", deserialize_with = "crate::de::deserialize_u64")]
+    pub steam_id: u64,
+    #[serde(alias = "personaname", default)]
+    pub persona_name: String,
+    #[serde(alias = "profileurl", default)]
+    pub profile_url: String,
+    /// The 184x184 avatar.
+    #[serde(alias = "avatarfull", default)]
+    pub avatar_url: String,
+}
+
+/// Client for `ISteamUser/GetPlayerSummaries/v2/`, used to resolve Workshop creators.
+///
+/// Like [`QueryFilesClient`](crate::QueryFilesClient) it needs a Steam Web API key.
+/// Summaries are kept in memory for the lifetime of the client and shared by its clones,
+/// including the IDs Steam did not return a profile for.
+#[derive(Debug, Clone)]
+pub struct PlayerSummariesClient {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+    policy: SharedRequestPolicy,
+    cache: Arc<Mutex<HashMap<u64, Option<PlayerSummary>>>>,
+}
+
+impl Default for PlayerSummariesClient {
+    fn default() -> Self {
+        PlayerSummariesClient::from_endpoint(Self::DEFAULT_ENDPOINT.to_string())
+    }
+}
+
+#[derive(Deserialize)]
+struct SummariesResponse {
+    response: Players,
+}
+
+#[derive(Deserialize)]
+struct Players {
+    #[serde(default)]
+    players: Vec<PlayerSummary>,
+}
+
+impl PlayerSummariesClient {
+    const DEFAULT_ENDPOINT: &'static str =
+        "https://api.steampowered.com/ISteamUser/GetPlayerSummaries/v2/";
+    /// Steam answers for at most this many IDs per request.
+    pub const MAX_IDS_PER_REQUEST: usize = 100;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_endpoint(endpoint: String) -> Self {
+        PlayerSummariesClient {
+            client: reqwest::Client::new(),
+            endpoint,
+            api_key: None,
+            policy: SharedRequestPolicy::default(),
+            cache: Arc::default(),
+        }
+    }
+
+    pub fn set_api_key(&mut self, api_key: Option<String>) -> &mut Self {
+        self.api_key = api_key.filter(|key| !key.trim().is_empty());
+        self
+    }
+
+    /// Throttles and retries requests according to `policy`.
+    pub fn with_request_policy(mut self, policy: impl Into<SharedRequestPolicy>) -> Self {
+        self.policy = policy.into();
+        self
+    }
+
+    fn cache(&self) -> MutexGuard<'_, HashMap<u64, Option<PlayerSummary>>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Profiles of `steam_ids` in the order given, skipping duplicates and IDs Steam
+    /// has no public profile for.
+    ///
+    /// Only IDs missing from the cache are requested, in batches of
+    /// [`PlayerSummariesClient::MAX_IDS_PER_REQUEST`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MissingApiKey`] when a lookup is needed but no API key is set.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use steam_api::PlayerSummariesClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut client = PlayerSummariesClient::new();
+    /// client.set_api_key(Some("0123456789ABCDEF".to_string()));
+    /// for player in client.get_player_summaries(&[76561197960435530]).await? {
+    ///     println!("{} {}", player.persona_name, player.profile_url);
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn get_player_summaries(
+        &self,
+        steam_ids: &[u64],
+    ) -> Result<Vec<PlayerSummary>, Error> {
+        let mut missing = steam_ids
+            .iter()
+            .copied()
+            .filter(|id| !self.cache().contains_key(id))
+            .collect::<Vec<u64>>();
+        missing.sort_unstable();
+        missing.dedup();
+
+        if !missing.is_empty() {
+            let key = self.api_key.as_deref().ok_or(Error::MissingApiKey)?;
+            let batches: Vec<Vec<u64>> = missing
+                .chunks(Self::MAX_IDS_PER_REQUEST)
+                .map(<[u64]>::to_vec)
+                .collect();
+            let batches: Vec<Vec<PlayerSummary>> = stream::iter(batches)
+                .map(|batch| self.fetch(key, batch))
+                .buffered(self.policy.get().concurrency.max(1))
+                .try_collect()
+                .await?;
+
+            let mut cache = self.cache();
+            missing.iter().for_each(|&id| {
+                cache.insert(id, None);
+            });
+            batches.into_iter().flatten().for_each(|player| {
+                cache.insert(player.steam_id, Some(player));
+            });
+        }
+
+        let cache = self.cache();
+        let mut seen = HashSet::new();
+        Ok(steam_ids
+            .iter()
+            .filter(|&&id| seen.insert(id))
+            .filter_map(|id| cache.get(id).cloned().flatten())
+            .collect())
+    }
+
+    async fn fetch(&self, key: &str, steam_ids: Vec<u64>) -> Result<Vec<PlayerSummary>, Error> {
+        let steam_ids = steam_ids
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<String>>()
+            .join(",");
+        let response: SummariesResponse = request::send(self.policy.get(), || {
+            self.client
+                .get(self.endpoint.as_str())
+                .query(&[("key", key), ("steamids", steam_ids.as_str())])
+        })
+        .await?
+        .json()
+        .await?;
+        Ok(response.response.players)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RequestPolicy;
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+
+    const PATH: &str = "/ISteamUser/GetPlayerSummaries/v2/";
+
+    fn client(server: &Server) -> PlayerSummariesClient {
+        let mut client = PlayerSummariesClient::from_endpoint(format!("{}{PATH}", server.url()));
+        client.set_api_key(Some("KEY".to_string()));
+        client
+    }
+
+    #[tokio::test]
+    async fn test_summaries_are_cached() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", PATH)
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("key".into(), "KEY".into()),
+                Matcher::UrlEncoded("steamids".into(), "1,2,3".into()),
+            ]))
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(
+                json!({
+                    "response": {
+                        "players": [
+                            {
+                                "steamid": "2",
+                                "personaname": "Two",
+                                "profileurl": "https://steamcommunity.com/id/two/",
+                                "avatarfull": "https://avatars.example/two_full.jpg"
+                            },
+                            { "steamid": "1", "personaname": "One" }
+                        ]
+                    }
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+
+        let client = client(&server);
+        let players = client.get_player_summaries(&[3, 2, 1, 2]).await.unwrap();
+        assert_eq!(
+            players.iter().map(|p| p.steam_id).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        assert_eq!(
+            players[0].avatar_url,
+            "https://avatars.example/two_full.jpg"
+        );
+
+        // A clone shares the cache, unknown ID 3 is not requested again.
+        let players = client.clone().get_player_summaries(&[1, 3]).await.unwrap();
+        assert_eq!(players[0].persona_name, "One");
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_summaries_require_api_key() {
+        let result = PlayerSummariesClient::new()
+            .get_player_summaries(&[1])
+            .await;
+        assert!(matches!(result, Err(Error::MissingApiKey)));
+        let empty = PlayerSummariesClient::new().get_player_summaries(&[]).await;
+        assert!(empty.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_request_policy_is_shared() {
+        let shared = SharedRequestPolicy::default();
+        let client = PlayerSummariesClient::new().with_request_policy(shared.clone());
+        let clone = client.clone();
+        let policy = RequestPolicy {
+            max_retries: 7,
+            ..RequestPolicy::default()
+        };
+        shared.set(policy);
+        assert_eq!(clone.policy.get(), policy);
+    }
+}
@@ -0,0 +1,392 @@
+use crate::request::{self, SharedRequestPolicy};
+use crate::{Error, WorkshopItem};
+use serde::{Deserialize, Serialize};
+
+/// Client for `IPublishedFileService/QueryFiles/v1/`, used to browse and search the Workshop.
+///
+/// Unlike [`SteamWorkShopClient`](crate::SteamWorkShopClient) this endpoint needs a
+/// Steam Web API key.
+#[derive(Debug, Clone)]
+pub struct QueryFilesClient {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+    policy: SharedRequestPolicy,
+}
+
+impl Default for QueryFilesClient {
+    fn default() -> Self {
+        QueryFilesClient::from_endpoint(Self::DEFAULT_ENDPOINT.to_string())
+    }
+}
+
+/// How [`QueryFilesClient::query`] orders its results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Ranking {
+    /// Most popular over the last [`WorkshopQuery::days`] days.
+    #[default]
+    Trend,
+    MostSubscribed,
+    MostRecent,
+    /// Best match for [`WorkshopQuery::search_text`].
+    TextSearch,
+}
+
+impl Ranking {
+    /// The `EPublishedFileQueryType` Steam expects as `query_type`.
+    fn query_type(self) -> u32 {
+        match self {
+            Ranking::MostRecent => 1,
+            Ranking::Trend => 3,
+            Ranking::MostSubscribed => 9,
+            Ranking::TextSearch => 12,
+        }
+    }
+}
+
+/// One page request against [`QueryFilesClient`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WorkshopQuery {
+    pub app_id: u64,
+    pub ranking: Ranking,
+    /// Only items carrying every one of these tags are returned.
+    pub tags: Vec<String>,
+    pub search_text: Option<String>,
+    /// Time window of [`Ranking::Trend`] in days.
+    pub days: u32,
+    /// [`QueryPage::next_cursor`] of the previous page, `None` for the first page.
+    pub cursor: Option<String>,
+    /// Items per page, Steam caps this at 100.
+    pub per_page: u32,
+}
+
+impl Default for WorkshopQuery {
+    fn default() -> Self {
+        WorkshopQuery {
+            app_id: 0,
+            ranking: Ranking::default(),
+            tags: vec![],
+            search_text: None,
+            days: 7,
+            cursor: None,
+            per_page: 30,
+        }
+    }
+}
+
+impl WorkshopQuery {
+    pub fn new(app_id: u64, ranking: Ranking) -> Self {
+        WorkshopQuery {
+            app_id,
+            ranking,
+            ..WorkshopQuery::default()
+        }
+    }
+
+    /// A [`Ranking::TextSearch`] query for `text`.
+    pub fn search(app_id: u64, text: impl Into<String>) -> Self {
+        WorkshopQuery {
+            search_text: Some(text.into()),
+            ..WorkshopQuery::new(app_id, Ranking::TextSearch)
+        }
+    }
+
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+
+    pub fn with_per_page(mut self, per_page: u32) -> Self {
+        self.per_page = per_page;
+        self
+    }
+
+    fn params(&self, key: &str) -> Vec<(String, String)> {
+        let mut params = vec![
+            ("key".to_string(), key.to_string()),
+            ("appid".to_string(), self.app_id.to_string()),
+            (
+                "query_type".to_string(),
+                self.ranking.query_type().to_string(),
+            ),
+            (
+                "cursor".to_string(),
+                self.cursor.clone().unwrap_or_else(|| "*".to_string()),
+            ),
+            (
+                "numperpage".to_string(),
+                self.per_page.clamp(1, 100).to_string(),
+            ),
+            ("return_tags".to_string(), "true".to_string()),
+            ("return_metadata".to_string(), "true".to_string()),
+        ];
+        if self.ranking == Ranking::Trend {
+            params.push(("days".to_string(), self.days.to_string()));
+        }
+        if let Some(text) = self.search_text.as_ref().filter(|text| !text.is_empty()) {
+            params.push(("search_text".to_string(), text.clone()));
+        }
+        params.extend(
+            self.tags
+                .iter()
+                .enumerate()
+                .map(|(idx, tag)| (format!("requiredtags[{idx}]"), tag.clone())),
+        );
+        params
+    }
+}
+
+/// One page of [`QueryFilesClient::query`] results.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryPage {
+    /// Number of items matching the query across all pages.
+    pub total: u64,
+    pub items: Vec<WorkshopItem>,
+    /// Cursor of the following page, `None` on the last page.
+    pub next_cursor: Option<String>,
+}
+
+impl QueryFilesClient {
+    const DEFAULT_ENDPOINT: &'static str =
+        "https://api.steampowered.com/IPublishedFileService/QueryFiles/v1/";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_endpoint(endpoint: String) -> Self {
+        QueryFilesClient {
+            client: reqwest::Client::new(),
+            endpoint,
+            api_key: None,
+            policy: SharedRequestPolicy::default(),
+        }
+    }
+
+    pub fn set_api_key(&mut self, api_key: Option<String>) -> &mut Self {
+        self.api_key = api_key.filter(|key| !key.trim().is_empty());
+        self
+    }
+
+    /// Times out and retries requests according to `policy`.
+    pub fn with_request_policy(mut self, policy: impl Into<SharedRequestPolicy>) -> Self {
+        self.policy = policy.into();
+        self
+    }
+
+    /// Fetches one page of Workshop items matching `query`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MissingApiKey`] when no API key is set,
+    /// [`Error::RateLimited`] when Steam keeps rate limiting the request, or
+    /// [`Error::Request`] when Steam rejects the request, e.g. for an invalid key.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use steam_api::{QueryFilesClient, Ranking, WorkshopQuery};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut client = QueryFilesClient::new();
+    /// client.set_api_key(Some("0123456789ABCDEF".to_string()));
+    /// let page = client
+    ///     .query(&WorkshopQuery::new(602960, Ranking::MostSubscribed))
+    ///     .await?;
+    /// if let Some(cursor) = page.next_cursor {
+    ///     let query = WorkshopQuery::new(602960, Ranking::MostSubscribed).with_cursor(cursor);
+    ///     client.query(&query).await?;
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn query(&self, query: &WorkshopQuery) -> Result<QueryPage, Error> {
+        let key = self.api_key.as_deref().ok_or(Error::MissingApiKey)?;
+        let params = query.params(key);
+        let response: QueryResponse = request::send(self.policy.get(), || {
+            self.client.get(self.endpoint.as_str()).query(&params)
+        })
+        .await?
+        .json()
+        .await?;
+
+        let response = response.response;
+        // Steam hands back the cursor it was given once there is nothing left.
+        let next_cursor = response.next_cursor.filter(|next| {
+            !response.published_file_details.is_empty()
+                && query.cursor.as_deref().unwrap_or("*") != next
+        });
+        Ok(QueryPage {
+            total: response.total,
+            items: response.published_file_details,
+            next_cursor,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct QueryResponse {
+    response: QueryFilesResponse,
+}
+
+#[derive(Deserialize)]
+struct QueryFilesResponse {
+    #[serde(default)]
+    total: u64,
+    #[serde(default, rename = "publishedfiledetails")]
+    published_file_details: Vec<WorkshopItem>,
+    next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RequestPolicy;
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+
+    const PATH: &str = "/IPublishedFileService/QueryFiles/v1/";
+
+    fn client(server: &Server) -> QueryFilesClient {
+        let mut client = QueryFilesClient::from_endpoint(format!("{}{PATH}", server.url()));
+        client.set_api_key(Some("KEY".to_string()));
+        client
+    }
+
+    #[tokio::test]
+    async fn test_query_first_page() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", PATH)
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("key".into(), "KEY".into()),
+                Matcher::UrlEncoded("appid".into(), "602960".into()),
+                Matcher::UrlEncoded("query_type".into(), "12".into()),
+                Matcher::UrlEncoded("cursor".into(), "*".into()),
+                Matcher::UrlEncoded("numperpage".into(), "2".into()),
+                Matcher::UrlEncoded("search_text".into(), "reactor".into()),
+                Matcher::UrlEncoded("requiredtags[0]".into(), "Submarine".into()),
+            ]))
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(
+                json!({
+                    "response": {
+                        "total": 3,
+                        "publishedfiledetails": [
+                            {
+                                "result": 1,
+                                "publishedfileid": "1",
+                                "creator": "76561198000000000",
+                                "creator_appid": 602960,
+                                "consumer_appid": 602960,
+                                "file_size": "2048",
+                                "title": "Reactor Sub",
+                                "file_description": "Big reactor.",
+                                "subscriptions": 10,
+                                "tags": [{ "tag": "Submarine", "display_name": "Submarine" }]
+                            },
+                            { "result": 1, "publishedfileid": "2", "title": "Reactor Mod" }
+                        ],
+                        "next_cursor": "AoJ4"
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let query = WorkshopQuery::search(602960, "reactor")
+            .with_tags(["Submarine"])
+            .with_per_page(2);
+        let page = client(&server).query(&query).await.unwrap();
+
+        mock.assert();
+        assert_eq!(page.total, 3);
+        assert_eq!(page.next_cursor.as_deref(), Some("AoJ4"));
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.items[0].published_file_id, 1);
+        assert_eq!(page.items[0].consumer_app_id, 602960);
+        assert_eq!(page.items[0].description, "Big reactor.");
+        assert_eq!(page.items[0].file_size_bytes(), 2048);
+        assert_eq!(page.items[0].tag_names(), vec!["Submarine"]);
+    }
+
+    #[tokio::test]
+    async fn test_query_last_page_has_no_cursor() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", PATH)
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("cursor".into(), "AoJ4".into()),
+                Matcher::UrlEncoded("query_type".into(), "3".into()),
+                Matcher::UrlEncoded("days".into(), "7".into()),
+            ]))
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(json!({ "response": { "total": 3, "next_cursor": "AoJ4" } }).to_string())
+            .create();
+
+        let query = WorkshopQuery::new(602960, Ranking::Trend).with_cursor("AoJ4");
+        let page = client(&server).query(&query).await.unwrap();
+
+        assert!(page.items.is_empty());
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[tokio::test]
+    async fn test_query_requires_api_key() {
+        let result = QueryFilesClient::new()
+            .query(&WorkshopQuery::new(602960, Ranking::MostRecent))
+            .await;
+        assert!(matches!(result, Err(Error::MissingApiKey)));
+    }
+
+    #[tokio::test]
+    async fn test_query_rejected_key() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", PATH)
+            .match_query(Matcher::Any)
+            .with_status(403)
+            .create();
+
+        let result = client(&server)
+            .query(&WorkshopQuery::new(602960, Ranking::MostRecent))
+            .await;
+        assert!(matches!(result, Err(Error::Request(_))));
+    }
+
+    #[tokio::test]
+    async fn test_query_retries_rate_limit() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", PATH)
+            .match_query(Matcher::Any)
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(2)
+            .create();
+
+        let client = client(&server).with_request_policy(RequestPolicy {
+            max_retries: 1,
+            ..RequestPolicy::default()
+        });
+        let result = client
+            .query(&WorkshopQuery::new(602960, Ranking::MostRecent))
+            .await;
+        mock.assert();
+        assert!(matches!(
+            result,
+            Err(Error::RateLimited {
+                retry_after: Some(_)
+            })
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::Error;
+use logger::warn;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How requests to the Steam Web API are throttled and retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestPolicy {
+    /// How many batches of a batched lookup are in flight at once, at least 1.
+    pub concurrency: usize,
+    /// Timeout of a single request, including reading the response.
+    pub timeout: Duration,
+    /// How many more times a rate limited, failed or timed out request is sent.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every further retry.
+    pub backoff: Duration,
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        RequestPolicy {
+            concurrency: 4,
+            timeout: Duration::from_secs(15),
+            max_retries: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RequestPolicy {
+    /// Longest `Retry-After` that is waited out, anything longer fails with
+    /// [`Error::RateLimited`] right away.
+    pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+    /// Delay before the `retry`-th retry, starting at 1.
+    fn delay(&self, retry: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+    }
+}
+
+/// A [`RequestPolicy`] handed to several clients, changing it applies to all of them.
+#[derive(Debug, Clone, Default)]
+pub struct SharedRequestPolicy(Arc<Mutex<RequestPolicy>>);
+
+impl SharedRequestPolicy {
+    pub fn new(policy: RequestPolicy) -> Self {
+        SharedRequestPolicy(Arc::new(Mutex::new(policy)))
+    }
+
+    pub fn get(&self) -> RequestPolicy {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set(&self, policy: RequestPolicy) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = policy;
+    }
+}
+
+impl From<RequestPolicy> for SharedRequestPolicy {
+    fn from(policy: RequestPolicy) -> Self {
+        SharedRequestPolicy::new(policy)
+    }
+}
+
+/// Sends the request built by `request` under `policy`.
+///
+/// 429 and 5xx responses, timeouts and connection errors are retried with exponential
+/// backoff, waiting for `Retry-After` instead when Steam sends one. A request that is
+/// still rate limited afterward fails with [`Error::RateLimited`], other error statuses
+/// with [`Error::Request`].
+pub(crate) async fn send(
+    policy: RequestPolicy,
+    request: impl Fn() -> RequestBuilder,
+) -> Result<Response, Error> {
+    let mut retry = 0;
+    loop {
+        let result = request().timeout(policy.timeout).send().await;
+        let wait = match result {
+            Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                let retry_after = retry_after(&response);
+                if retry >= policy.max_retries
+                    || retry_after.is_some_and(|wait| wait > RequestPolicy::MAX_RETRY_AFTER)
+                {
+                    return Err(Error::RateLimited { retry_after });
+                }
+                retry_after.unwrap_or_else(|| policy.delay(retry + 1))
+            }
+            Ok(response) if response.status().is_server_error() && retry < policy.max_retries => {
+                retry_after(&response)
+                    .filter(|&wait| wait <= RequestPolicy::MAX_RETRY_AFTER)
+                    .unwrap_or_else(|| policy.delay(retry + 1))
+            }
+            Ok(response) => return Ok(response.error_for_status()?),
+            Err(e) if (e.is_timeout() || e.is_connect()) && retry < policy.max_retries => {
+                policy.delay(retry + 1)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        retry += 1;
+        warn!(
+            "Steam Web API request failed, retry {}/{} in {:?}",
+            retry, policy.max_retries, wait
+        );
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// `Retry-After` of `response` in delay-seconds; HTTP dates are ignored.
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+
+    fn policy(max_retries: u32) -> RequestPolicy {
+        RequestPolicy {
+            max_retries,
+            backoff: Duration::ZERO,
+            ..RequestPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles() {
+        let policy = RequestPolicy::default();
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let mut server = Server::new_async().await;
+        let limited = server
+            .mock("GET", "/")
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(1)
+            .create();
+        let unavailable = server.mock("GET", "/").with_status(503).expect(1).create();
+        let ok = server.mock("GET", "/").with_status(200).expect(1).create();
+
+        let client = reqwest::Client::new();
+        let response = send(policy(2), || client.get(server.url())).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        limited.assert();
+        unavailable.assert();
+        ok.assert();
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_after_retries() {
+        let mut server = Server::new_async().await;
+        let limited = server
+            .mock("GET", "/")
+            .with_status(429)
+            .with_header("Retry-After", "0")
+            .expect(2)
+            .create();
+
+        let client = reqwest::Client::new();
+        let result = send(policy(1), || client.get(server.url())).await;
+
+        limited.assert();
+        assert!(matches!(
+            result,
+            Err(Error::RateLimited {
+                retry_after: Some(Duration::ZERO)
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_long_retry_after_is_not_waited() {
+        let mut server = Server::new_async().await;
+        let limited = server
+            .mock("GET", "/")
+            .with_status(429)
+            .with_header("Retry-After", "3600")
+            .expect(1)
+            .create();
+
+        let client = reqwest::Client::new();
+        let result = send(policy(3), || client.get(server.url())).await;
+
+        limited.assert();
+        assert!(matches!(result, Err(Error::RateLimited { .. })));
+    }
+}
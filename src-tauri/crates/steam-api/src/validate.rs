@@ -0,0 +1,234 @@
+use crate::{Error, ItemOutcome, SteamWorkShopClient, WorkshopItem};
+use serde::{Deserialize, Serialize};
+
+/// Who can see a Workshop item, with bans taking precedence over visibility.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Availability {
+    Public,
+    FriendsOnly,
+    Private,
+    /// Only reachable through a direct link, downloads still work.
+    Unlisted,
+    Banned {
+        reason: String,
+    },
+}
+
+impl Availability {
+    /// Whether anonymous SteamCMD downloads of the item can succeed.
+    pub fn is_downloadable(&self) -> bool {
+        matches!(self, Availability::Public | Availability::Unlisted)
+    }
+}
+
+impl WorkshopItem {
+    /// Typed view of [`WorkshopItem::visibility`] and [`WorkshopItem::banned`].
+    ///
+    /// Visibility values Steam does not document are treated as [`Availability::Private`].
+    pub fn availability(&self) -> Availability {
+        if self.is_banned() {
+            return Availability::Banned {
+                reason: self.ban_reason.clone(),
+            };
+        }
+        match self.visibility {
+            0 => Availability::Public,
+            1 => Availability::FriendsOnly,
+            3 => Availability::Unlisted,
+            _ => Availability::Private,
+        }
+    }
+}
+
+/// Why an item should not be downloaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ValidationIssue {
+    /// Steam returned no details, `result` is its result code.
+    NotFound {
+        result: i32,
+    },
+    /// The item belongs to another game.
+    #[serde(rename_all = "camelCase")]
+    WrongApp {
+        consumer_app_id: u64,
+    },
+    Banned {
+        reason: String,
+    },
+    /// Friends-only or private, anonymous downloads fail.
+    Hidden {
+        availability: Availability,
+    },
+}
+
+/// Result of [`SteamWorkShopClient::validate_items`] for one item.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemValidation {
+    pub item_id: u64,
+    /// The item's details, when Steam returned them.
+    pub item: Option<Box<WorkshopItem>>,
+    /// Empty when the item can be downloaded.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ItemValidation {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn from_outcome(outcome: ItemOutcome, app_id: u64) -> Self {
+        let item_id = outcome.item_id();
+        let (item, issues) = match outcome {
+            ItemOutcome::Found(item) => {
+                let mut issues = Vec::new();
+                if item.consumer_app_id != app_id {
+                    issues.push(ValidationIssue::WrongApp {
+                        consumer_app_id: item.consumer_app_id,
+                    });
+                }
+                match item.availability() {
+                    Availability::Banned { reason } => {
+                        issues.push(ValidationIssue::Banned { reason })
+                    }
+                    availability if !availability.is_downloadable() => {
+                        issues.push(ValidationIssue::Hidden { availability })
+                    }
+                    _ => {}
+                }
+                (Some(item), issues)
+            }
+            ItemOutcome::NotFound { result, .. } => {
+                (None, vec![ValidationIssue::NotFound { result }])
+            }
+            ItemOutcome::Private { .. } => (
+                None,
+                vec![ValidationIssue::Hidden {
+                    availability: Availability::Private,
+                }],
+            ),
+            ItemOutcome::Banned { reason, .. } => (None, vec![ValidationIssue::Banned { reason }]),
+        };
+        ItemValidation {
+            item_id,
+            item,
+            issues,
+        }
+    }
+}
+
+impl SteamWorkShopClient {
+    /// Check many items at once before downloading them.
+    ///
+    /// Reports items that are missing, made for another game than `app_id`, banned, or
+    /// hidden from anonymous downloads. Results keep the order of `item_ids`; lookups are
+    /// batched like [`SteamWorkShopClient::get_item_outcomes_batched`] and bypass the
+    /// metadata cache.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use steam_api::SteamWorkShopClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = SteamWorkShopClient::new();
+    /// for validation in client.validate_items(vec![3354525188, 1234567890], 602960, 50).await? {
+    ///     if !validation.is_valid() {
+    ///         println!("{}: {:?}", validation.item_id, validation.issues);
+    ///     }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn validate_items(
+        &self,
+        item_ids: Vec<u64>,
+        app_id: u64,
+        batch_size: usize,
+    ) -> Result<Vec<ItemValidation>, Error> {
+        Ok(self
+            .get_item_outcomes_batched(item_ids, batch_size)
+            .await?
+            .into_iter()
+            .map(|outcome| ItemValidation::from_outcome(outcome, app_id))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_validate_items() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(
+                json!({
+                    "response": {
+                        "result": 1,
+                        "resultcount": 6,
+                        "publishedfiledetails": [
+                            { "publishedfileid": "1", "result": 1, "consumer_app_id": 602960, "visibility": 0 },
+                            { "publishedfileid": "2", "result": 1, "consumer_app_id": 440, "visibility": 3 },
+                            { "publishedfileid": "3", "result": 1, "consumer_app_id": 602960, "visibility": 1 },
+                            {
+                                "publishedfileid": "4", "result": 1, "consumer_app_id": 602960,
+                                "banned": 1, "ban_reason": "Stolen content"
+                            },
+                            { "publishedfileid": "5", "result": 9 },
+                            { "publishedfileid": "6", "result": 15 }
+                        ]
+                    }
+                })
+                .to_string(),
+            )
+            .create();
+
+        let client = SteamWorkShopClient::from_endpoint(server.url());
+        let validations = client
+            .validate_items(vec![1, 2, 3, 4, 5, 6], 602960, 0)
+            .await
+            .unwrap();
+
+        assert!(validations[0].is_valid());
+        assert_eq!(
+            validations[0].item.as_ref().unwrap().availability(),
+            Availability::Public
+        );
+        assert_eq!(
+            validations[1].issues,
+            vec![ValidationIssue::WrongApp {
+                consumer_app_id: 440
+            }]
+        );
+        assert_eq!(
+            validations[2].issues,
+            vec![ValidationIssue::Hidden {
+                availability: Availability::FriendsOnly
+            }]
+        );
+        assert_eq!(
+            validations[3].issues,
+            vec![ValidationIssue::Banned {
+                reason: "Stolen content".to_string()
+            }]
+        );
+        assert_eq!(
+            validations[4].issues,
+            vec![ValidationIssue::NotFound { result: 9 }]
+        );
+        assert_eq!(
+            validations[5].issues,
+            vec![ValidationIssue::Hidden {
+                availability: Availability::Private
+            }]
+        );
+    }
+}
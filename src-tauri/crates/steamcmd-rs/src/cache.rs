@@ -0,0 +1,214 @@
+use crate::{Error, SteamCMD};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// How a workshop cache entry relates to the installed mods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CacheState {
+    /// The mod is installed as a separate copy, the cache entry is a duplicate.
+    Copied,
+    /// The installed mod is a symlink to this entry, removing it breaks the mod.
+    Linked,
+    /// No installed mod uses this entry.
+    Orphaned,
+}
+
+/// One item in SteamCMD's workshop content folder.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    pub item_id: u64,
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub state: CacheState,
+}
+
+/// Which cache entries [`SteamCMD::prune_cache`] removes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneOptions {
+    /// Remove entries no installed mod uses.
+    pub orphaned: bool,
+    /// Remove entries whose mod is installed as a copy.
+    pub copied: bool,
+    /// Only report what would be removed.
+    pub dry_run: bool,
+}
+
+/// What a prune removed, or would remove on a dry run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneReport {
+    pub removed: Vec<CacheEntry>,
+    pub bytes_reclaimed: u64,
+    pub dry_run: bool,
+    /// Entries that could not be removed, with the reason.
+    pub errors: Vec<(u64, String)>,
+}
+
+impl SteamCMD {
+    /// Lists the workshop cache of `app_id`, classified against the installed mods.
+    ///
+    /// `installed` maps workshop item IDs to the folder the mod is installed in.
+    pub fn cache_entries(
+        &self,
+        app_id: u64,
+        installed: &HashMap<u64, PathBuf>,
+    ) -> Result<Vec<CacheEntry>, Error> {
+        let mut app_dir = self.home_dir()?.clone();
+        app_dir.extend(Self::WORKSHOP_DIR_BASE);
+        app_dir.push(app_id.to_string());
+
+        let read_dir = match std::fs::read_dir(&app_dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Error::Cache(e)),
+        };
+
+        let mut entries: Vec<CacheEntry> = read_dir
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .filter_map(|entry| {
+                let item_id = entry.file_name().to_str()?.parse::<u64>().ok()?;
+                let path = entry.path();
+                let state = match installed.get(&item_id) {
+                    Some(installed) if is_link_to(installed, &path) => CacheState::Linked,
+                    Some(installed) if installed.exists() => CacheState::Copied,
+                    _ => CacheState::Orphaned,
+                };
+                Some(CacheEntry {
+                    item_id,
+                    bytes: fs_utils::directory_size(&path),
+                    path,
+                    state,
+                })
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.item_id);
+        Ok(entries)
+    }
+
+    /// Removes orphaned and/or copied cache entries as selected by `options`.
+    ///
+    /// Linked entries are never removed since the installed mod points into them.
+    pub fn prune_cache(
+        &self,
+        app_id: u64,
+        installed: &HashMap<u64, PathBuf>,
+        options: PruneOptions,
+    ) -> Result<PruneReport, Error> {
+        let mut report = PruneReport {
+            dry_run: options.dry_run,
+            ..PruneReport::default()
+        };
+        let entries = self.cache_entries(app_id, installed)?;
+        let targets = entries.into_iter().filter(|entry| match entry.state {
+            CacheState::Orphaned => options.orphaned,
+            CacheState::Copied => options.copied,
+            CacheState::Linked => false,
+        });
+
+        for entry in targets {
+            if !options.dry_run
+                && let Err(e) = std::fs::remove_dir_all(&entry.path)
+            {
+                report.errors.push((entry.item_id, e.to_string()));
+                continue;
+            }
+            report.bytes_reclaimed += entry.bytes;
+            report.removed.push(entry);
+        }
+        Ok(report)
+    }
+}
+
+/// Whether `installed` is a symlink that resolves to `cache_dir`.
+fn is_link_to(installed: &Path, cache_dir: &Path) -> bool {
+    let is_symlink =
+        std::fs::symlink_metadata(installed).is_ok_and(|meta| meta.file_type().is_symlink());
+    match (installed.canonicalize(), cache_dir.canonicalize()) {
+        (Ok(target), Ok(cache_dir)) => is_symlink && target == cache_dir,
+        _ => false,
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_classify_and_prune() {
+        let home = TempDir::new().unwrap();
+        let mods = TempDir::new().unwrap();
+        let steamcmd = SteamCMD::new(home.path().to_path_buf());
+        for id in [1, 2, 3] {
+            let dir = steamcmd.workshop_item_dir(602960, id).unwrap();
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("filelist.xml"), b"0123456789").unwrap();
+        }
+
+        let copied = mods.path().join("1");
+        std::fs::create_dir(&copied).unwrap();
+        let linked = mods.path().join("2");
+        std::os::unix::fs::symlink(steamcmd.workshop_item_dir(602960, 2).unwrap(), &linked)
+            .unwrap();
+        let installed = HashMap::from([(1, copied), (2, linked)]);
+
+        let states: Vec<_> = steamcmd
+            .cache_entries(602960, &installed)
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.item_id, entry.state, entry.bytes))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                (1, CacheState::Copied, 10),
+                (2, CacheState::Linked, 10),
+                (3, CacheState::Orphaned, 10),
+            ]
+        );
+
+        let everything = PruneOptions {
+            orphaned: true,
+            copied: true,
+            dry_run: true,
+        };
+        let dry = steamcmd
+            .prune_cache(602960, &installed, everything)
+            .unwrap();
+        assert_eq!(dry.bytes_reclaimed, 20);
+        assert!(steamcmd.is_item_downloaded(602960, 1));
+
+        let report = steamcmd
+            .prune_cache(
+                602960,
+                &installed,
+                PruneOptions {
+                    dry_run: false,
+                    ..everything
+                },
+            )
+            .unwrap();
+        let removed: Vec<_> = report.removed.iter().map(|e| e.item_id).collect();
+        assert_eq!(removed, vec![1, 3]);
+        assert!(!steamcmd.is_item_downloaded(602960, 1));
+        assert!(steamcmd.is_item_downloaded(602960, 2));
+        assert!(!steamcmd.is_item_downloaded(602960, 3));
+    }
+
+    #[test]
+    fn test_missing_cache_is_empty() {
+        let home = TempDir::new().unwrap();
+        let steamcmd = SteamCMD::new(home.path().to_path_buf());
+        assert!(
+            steamcmd
+                .cache_entries(602960, &HashMap::new())
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt;
+
+/// Steam account used to log into SteamCMD.
+///
+/// Without a password SteamCMD reuses the session it cached for `username` on a
+/// previous interactive login, which is the only way to get past Steam Guard.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: Option<String>,
+}
+
+impl Credentials {
+    /// Builds credentials from optional config values, treating blank strings as unset.
+    ///
+    /// Returns `None` (anonymous login) when no username is given.
+    pub fn from_config(username: Option<&str>, password: Option<&str>) -> Option<Credentials> {
+        let username = username.map(str::trim).filter(|u| !u.is_empty())?;
+        Some(Credentials {
+            username: username.to_string(),
+            password: password.filter(|p| !p.is_empty()).map(str::to_string),
+        })
+    }
+
+    /// Whether this login relies on SteamCMD's cached session.
+    pub fn is_cached(&self) -> bool {
+        self.password.is_none()
+    }
+
+    /// The `login` line of a SteamCMD script.
+    pub(crate) fn login_command(credentials: Option<&Credentials>) -> String {
+        match credentials {
+            None => "+login anonymous".to_string(),
+            Some(Credentials {
+                username,
+                password: None,
+            }) => format!("+login {}", username),
+            Some(Credentials {
+                username,
+                password: Some(password),
+            }) => format!("+login {} {}", username, password),
+        }
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_login_command() {
+        assert_eq!(Credentials::login_command(None), "+login anonymous");
+
+        let cached = Credentials::from_config(Some("alice"), Some("")).unwrap();
+        assert!(cached.is_cached());
+        assert_eq!(Credentials::login_command(Some(&cached)), "+login alice");
+
+        let full = Credentials::from_config(Some("alice"), Some("hunter2")).unwrap();
+        assert_eq!(
+            Credentials::login_command(Some(&full)),
+            "+login alice hunter2"
+        );
+        assert!(!format!("{:?}", full).contains("hunter2"));
+
+        assert_eq!(Credentials::from_config(Some("  "), Some("x")), None);
+        assert_eq!(Credentials::from_config(None, None), None);
+    }
+}
@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Errors produced while locating or driving SteamCMD.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("SteamCMD home directory not set")]
+    HomeNotSet,
+
+    #[error("SteamCMD executable not found, tried: {}", display_paths(.tried))]
+    ExecutableNotFound { tried: Vec<PathBuf> },
+
+    #[error("Failed to write script: {0}")]
+    Script(std::io::Error),
+
+    #[error("Failed to run steamcmd: {0}")]
+    Spawn(std::io::Error),
+
+    #[error(
+        "Steam Guard code required for user {user}, log in once with steamcmd interactively to cache the session"
+    )]
+    SteamGuardRequired { user: String },
+
+    #[error("Invalid password for user {user}")]
+    InvalidPassword { user: String },
+
+    #[error("Login failed for user {user}: {reason}")]
+    LoginFailed { user: String, reason: String },
+
+    #[error("Download cancelled")]
+    Cancelled,
+
+    #[error("Failed to download SteamCMD: {0}")]
+    Fetch(reqwest::Error),
+
+    #[error("Failed to unpack SteamCMD: {0}")]
+    Unpack(std::io::Error),
+
+    #[error("SteamCMD was installed but failed to start: {0}")]
+    Verify(String),
+
+    #[error("Failed to read the workshop cache: {0}")]
+    Cache(std::io::Error),
+}
+
+impl Error {
+    /// Classifies the reason SteamCMD printed after `FAILED` on a login line.
+    pub(crate) fn login_failure(user: String, reason: String) -> Error {
+        const STEAM_GUARD_REASONS: [&str; 4] = [
+            "Account Logon Denied",
+            "Two-factor",
+            "Steam Guard",
+            "Invalid Login Auth Code",
+        ];
+        if reason.contains("Invalid Password") {
+            Error::InvalidPassword { user }
+        } else if STEAM_GUARD_REASONS.iter().any(|r| reason.contains(r)) {
+            Error::SteamGuardRequired { user }
+        } else {
+            Error::LoginFailed { user, reason }
+        }
+    }
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,243 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Sending half of a SteamCMD event stream.
+pub type EventSender = UnboundedSender<SteamCmdEvent>;
+
+/// A progress event parsed from a single line of SteamCMD output.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum SteamCmdEvent {
+    /// A SteamCMD process was spawned; emitted before any output is read.
+    ProcessStarted { pid: u32 },
+    /// SteamCMD started logging in, `user` is `anonymous` for anonymous sessions.
+    LoggingIn { user: String },
+    /// The login finished successfully.
+    LoggedIn { user: String },
+    /// `Logging in user '<user>' to Steam Public...FAILED (<reason>)`
+    LoginFailed { user: String, reason: String },
+    /// SteamCMD is prompting for a Steam Guard or two-factor code.
+    SteamGuardRequired,
+    /// `Downloading item <id> ...`
+    DownloadStarted { item_id: u64 },
+    /// `Success. Downloaded item <id> to "<path>" (<bytes> bytes)`
+    DownloadSucceeded {
+        item_id: u64,
+        path: PathBuf,
+        bytes: u64,
+    },
+    /// `ERROR! Download item <id> failed (<reason>).`
+    DownloadFailed { item_id: u64, reason: String },
+    /// Any line the parser does not recognize.
+    Output { line: String },
+}
+
+impl SteamCmdEvent {
+    /// Parses one line of SteamCMD stdout.
+    ///
+    /// Lines that are not recognized are returned as [`SteamCmdEvent::Output`].
+    pub fn parse(line: &str) -> SteamCmdEvent {
+        let clean = strip_ansi(line);
+        let trimmed = clean.trim();
+        Self::parse_login(trimmed)
+            .or_else(|| Self::parse_steam_guard(trimmed))
+            .or_else(|| Self::parse_download(trimmed))
+            .unwrap_or_else(|| SteamCmdEvent::Output {
+                line: trimmed.to_string(),
+            })
+    }
+
+    /// Returns the workshop item this event refers to, if any.
+    pub fn item_id(&self) -> Option<u64> {
+        match self {
+            SteamCmdEvent::DownloadStarted { item_id }
+            | SteamCmdEvent::DownloadSucceeded { item_id, .. }
+            | SteamCmdEvent::DownloadFailed { item_id, .. } => Some(*item_id),
+            _ => None,
+        }
+    }
+
+    fn parse_login(line: &str) -> Option<SteamCmdEvent> {
+        let (user, status) =
+            if let Some(rest) = line.strip_prefix("Connecting anonymously to Steam Public...") {
+                ("anonymous".to_string(), rest)
+            } else if let Some(rest) = line.strip_prefix("Logging in user '") {
+                let (user, rest) = rest.split_once('\'')?;
+                let (_, status) = rest.split_once("to Steam Public...")?;
+                (user.to_string(), status)
+            } else {
+                return None;
+            };
+
+        if status.starts_with("OK") {
+            Some(SteamCmdEvent::LoggedIn { user })
+        } else if status.is_empty() {
+            Some(SteamCmdEvent::LoggingIn { user })
+        } else if let Some(rest) = status.strip_prefix("FAILED") {
+            let reason = rest
+                .trim()
+                .strip_prefix('(')
+                .and_then(|r| r.rsplit_once(')'))
+                .map(|(reason, _)| reason.to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            Some(SteamCmdEvent::LoginFailed { user, reason })
+        } else {
+            None
+        }
+    }
+
+    fn parse_steam_guard(line: &str) -> Option<SteamCmdEvent> {
+        const PROMPTS: [&str; 2] = ["Steam Guard code:", "Two-factor code:"];
+        PROMPTS
+            .iter()
+            .any(|prompt| line.starts_with(prompt))
+            .then_some(SteamCmdEvent::SteamGuardRequired)
+    }
+
+    fn parse_download(line: &str) -> Option<SteamCmdEvent> {
+        if let Some(rest) = line.strip_prefix("Downloading item ") {
+            let item_id = leading_u64(rest)?;
+            return Some(SteamCmdEvent::DownloadStarted { item_id });
+        }
+
+        if let Some(rest) = line.strip_prefix("Success. Downloaded item ") {
+            let item_id = leading_u64(rest)?;
+            let (_, rest) = rest.split_once("to \"")?;
+            let (path, rest) = rest.split_once('"')?;
+            let bytes = rest
+                .trim()
+                .strip_prefix('(')
+                .and_then(leading_u64)
+                .unwrap_or_default();
+            return Some(SteamCmdEvent::DownloadSucceeded {
+                item_id,
+                path: PathBuf::from(path),
+                bytes,
+            });
+        }
+
+        if let Some(rest) = line.strip_prefix("ERROR! Download item ") {
+            let item_id = leading_u64(rest)?;
+            let reason = rest
+                .split_once('(')
+                .and_then(|(_, r)| r.rsplit_once(')'))
+                .map(|(reason, _)| reason.to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            return Some(SteamCmdEvent::DownloadFailed { item_id, reason });
+        }
+
+        None
+    }
+}
+
+/// Parses the decimal number at the start of `s`.
+fn leading_u64(s: &str) -> Option<u64> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s[..end].parse().ok()
+}
+
+/// Removes ANSI color sequences that SteamCMD emits on some terminals.
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_anonymous_login() {
+        assert_eq!(
+            SteamCmdEvent::parse("Connecting anonymously to Steam Public...OK"),
+            SteamCmdEvent::LoggedIn {
+                user: "anonymous".to_string()
+            }
+        );
+        assert_eq!(
+            SteamCmdEvent::parse("Logging in user 'alice' [U:1:42] to Steam Public..."),
+            SteamCmdEvent::LoggingIn {
+                user: "alice".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_login_failures() {
+        assert_eq!(
+            SteamCmdEvent::parse(
+                "Logging in user 'alice' [U:1:42] to Steam Public...FAILED (Invalid Password)"
+            ),
+            SteamCmdEvent::LoginFailed {
+                user: "alice".to_string(),
+                reason: "Invalid Password".to_string()
+            }
+        );
+        assert_eq!(
+            SteamCmdEvent::parse("Two-factor code:"),
+            SteamCmdEvent::SteamGuardRequired
+        );
+        assert_eq!(
+            SteamCmdEvent::parse("Steam Guard code:"),
+            SteamCmdEvent::SteamGuardRequired
+        );
+    }
+
+    #[test]
+    fn test_parse_download_lines() {
+        assert_eq!(
+            SteamCmdEvent::parse("Downloading item 2518816103 ..."),
+            SteamCmdEvent::DownloadStarted {
+                item_id: 2518816103
+            }
+        );
+        assert_eq!(
+            SteamCmdEvent::parse(
+                "Success. Downloaded item 2518816103 to \"/steam/steamapps/workshop/content/602960/2518816103\" (1048576 bytes) \r"
+            ),
+            SteamCmdEvent::DownloadSucceeded {
+                item_id: 2518816103,
+                path: PathBuf::from("/steam/steamapps/workshop/content/602960/2518816103"),
+                bytes: 1048576,
+            }
+        );
+        assert_eq!(
+            SteamCmdEvent::parse("ERROR! Download item 2518816103 failed (Timeout)."),
+            SteamCmdEvent::DownloadFailed {
+                item_id: 2518816103,
+                reason: "Timeout".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_and_ansi() {
+        assert_eq!(
+            SteamCmdEvent::parse("\x1b[0mLoading Steam API...OK"),
+            SteamCmdEvent::Output {
+                line: "Loading Steam API...OK".to_string()
+            }
+        );
+        assert_eq!(
+            SteamCmdEvent::parse("\x1b[1m Downloading item 7 ..."),
+            SteamCmdEvent::DownloadStarted { item_id: 7 }
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Scripted stand-in for SteamCMD, so downloads can be tested without Steam.
+//!
+//! [`FakeSteamCmd`] runs in-process: it reads the generated script, prints the
+//! lines the real SteamCMD would print and creates the item folders itself.
+
+use crate::runner::{Process, Runner, Spawned};
+use crate::{Error, SteamCMD};
+use futures::future::BoxFuture;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncWriteExt, DuplexStream};
+use tokio::task::JoinHandle;
+
+/// What the fake does when asked to download an item.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FakeOutcome {
+    /// Creates the item folder with the given `(name, content)` files and reports success.
+    Succeed(Vec<(String, Vec<u8>)>),
+    /// Reports `ERROR! Download item <id> failed (<reason>).`
+    Fail(String),
+    /// Creates a partial item folder and never finishes, until killed.
+    Hang,
+}
+
+impl FakeOutcome {
+    /// A successful download containing a single `filelist.xml`.
+    pub fn succeed() -> FakeOutcome {
+        FakeOutcome::Succeed(vec![(
+            "filelist.xml".to_string(),
+            b"<contentpackage />".to_vec(),
+        )])
+    }
+
+    pub fn fail(reason: &str) -> FakeOutcome {
+        FakeOutcome::Fail(reason.to_string())
+    }
+}
+
+/// How the fake answers the login line.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum FakeLogin {
+    #[default]
+    Ok,
+    /// Prints `FAILED (<reason>)` and waits for input forever, like SteamCMD does.
+    Fail(String),
+    /// Prompts for a Steam Guard code on an unfinished line and waits forever.
+    SteamGuard,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    login: FakeLogin,
+    outcomes: HashMap<u64, VecDeque<FakeOutcome>>,
+    scripts: Vec<String>,
+}
+
+/// A [`Runner`] that pretends to be SteamCMD.
+///
+/// Items without queued outcomes download successfully. Clones share their state,
+/// so a test can keep one to inspect [`FakeSteamCmd::scripts`] after the run.
+#[derive(Debug, Default, Clone)]
+pub struct FakeSteamCmd {
+    state: Arc<Mutex<FakeState>>,
+}
+
+impl FakeSteamCmd {
+    pub fn new() -> FakeSteamCmd {
+        FakeSteamCmd::default()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn with_login(self, login: FakeLogin) -> FakeSteamCmd {
+        self.state().login = login;
+        self
+    }
+
+    /// Queues one outcome per download attempt of `item_id`; the last one repeats.
+    pub fn with_item(
+        self,
+        item_id: u64,
+        outcomes: impl IntoIterator<Item = FakeOutcome>,
+    ) -> FakeSteamCmd {
+        self.state()
+            .outcomes
+            .insert(item_id, outcomes.into_iter().collect());
+        self
+    }
+
+    /// Every script the fake was asked to run, in order.
+    pub fn scripts(&self) -> Vec<String> {
+        self.state().scripts.clone()
+    }
+
+    fn next_outcome(&self, item_id: u64) -> FakeOutcome {
+        let mut state = self.state();
+        match state.outcomes.get_mut(&item_id) {
+            Some(queue) if queue.len() > 1 => queue.pop_front().expect("queue is not empty"),
+            Some(queue) => queue.front().cloned().unwrap_or_else(FakeOutcome::succeed),
+            None => FakeOutcome::succeed(),
+        }
+    }
+
+    async fn run(
+        &self,
+        home: PathBuf,
+        script: String,
+        mut out: DuplexStream,
+    ) -> std::io::Result<bool> {
+        let layout = SteamCMD::new(home);
+        for line in script.lines() {
+            let mut words = line.trim().trim_start_matches('+').split_whitespace();
+            match words.next() {
+                Some("login") => {
+                    let user = words.next().unwrap_or("anonymous");
+                    let prefix = if user == "anonymous" {
+                        "Connecting anonymously to Steam Public...".to_string()
+                    } else {
+                        format!("Logging in user '{}' [U:1:0] to Steam Public...", user)
+                    };
+                    let login = self.state().login.clone();
+                    match login {
+                        FakeLogin::Ok => write_line(&mut out, &format!("{prefix}OK")).await?,
+                        FakeLogin::Fail(reason) => {
+                            write_line(&mut out, &format!("{prefix}FAILED ({reason})")).await?;
+                            std::future::pending::<()>().await;
+                        }
+                        FakeLogin::SteamGuard => {
+                            // Prompts without a newline, like SteamCMD does
+                            out.write_all(b"Steam Guard code:").await?;
+                            std::future::pending::<()>().await;
+                        }
+                    }
+                }
+                Some("workshop_download_item") => {
+                    let mut ids = words.filter_map(|w| w.parse::<u64>().ok());
+                    let (Some(app_id), Some(item_id)) = (ids.next(), ids.next()) else {
+                        continue;
+                    };
+                    let dir = layout
+                        .workshop_item_dir(app_id, item_id)
+                        .map_err(std::io::Error::other)?;
+                    write_line(&mut out, &format!("Downloading item {item_id} ...")).await?;
+                    match self.next_outcome(item_id) {
+                        FakeOutcome::Succeed(files) => {
+                            let bytes = write_files(&dir, &files)?;
+                            let line = format!(
+                                "Success. Downloaded item {item_id} to \"{}\" ({bytes} bytes)",
+                                dir.display()
+                            );
+                            write_line(&mut out, &line).await?;
+                        }
+                        FakeOutcome::Fail(reason) => {
+                            let line = format!("ERROR! Download item {item_id} failed ({reason}).");
+                            write_line(&mut out, &line).await?;
+                        }
+                        FakeOutcome::Hang => {
+                            write_files(&dir, &[("partial".to_string(), b"...".to_vec())])?;
+                            std::future::pending::<()>().await;
+                        }
+                    }
+                }
+                Some("quit") => break,
+                _ => {}
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl Runner for FakeSteamCmd {
+    fn executable(&self, home: &Path) -> Result<PathBuf, Error> {
+        Ok(home.join("steamcmd-fake"))
+    }
+
+    fn spawn(&self, executable: &Path, script: &Path) -> Result<Spawned, Error> {
+        let script = std::fs::read_to_string(script).map_err(Error::Spawn)?;
+        self.state().scripts.push(script.clone());
+
+        let home = executable
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let (writer, stdout) = tokio::io::duplex(64 * 1024);
+        let fake = self.clone();
+        let task = tokio::spawn(async move { fake.run(home, script, writer).await });
+
+        Ok(Spawned {
+            pid: None,
+            stdout: Box::new(stdout),
+            stderr: Box::new(tokio::io::empty()),
+            process: Box::new(FakeProcess(task)),
+        })
+    }
+}
+
+struct FakeProcess(JoinHandle<std::io::Result<bool>>);
+
+impl Process for FakeProcess {
+    fn kill(&mut self) -> BoxFuture<'_, std::io::Result<()>> {
+        self.0.abort();
+        Box::pin(async { Ok(()) })
+    }
+
+    fn wait(&mut self) -> BoxFuture<'_, std::io::Result<bool>> {
+        Box::pin(async move {
+            match (&mut self.0).await {
+                Ok(result) => result,
+                Err(e) if e.is_cancelled() => Ok(false),
+                Err(e) => Err(std::io::Error::other(e)),
+            }
+        })
+    }
+}
+
+async fn write_line(out: &mut DuplexStream, line: &str) -> std::io::Result<()> {
+    out.write_all(line.as_bytes()).await?;
+    out.write_all(b"\n").await
+}
+
+fn write_files(dir: &Path, files: &[(String, Vec<u8>)]) -> std::io::Result<u64> {
+    std::fs::create_dir_all(dir)?;
+    for (name, content) in files {
+        std::fs::write(dir.join(name), content)?;
+    }
+    Ok(files.iter().map(|(_, content)| content.len() as u64).sum())
+}
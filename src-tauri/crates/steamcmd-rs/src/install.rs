@@ -0,0 +1,143 @@
+use crate::{Error, EventSender, SteamCMD};
+use flate2::read::GzDecoder;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Official SteamCMD archive for the current platform.
+#[cfg(windows)]
+pub const DEFAULT_STEAMCMD_URL: &str =
+    "https://steamcdn-a.akamaihd.net/client/installer/steamcmd.zip";
+#[cfg(target_os = "macos")]
+pub const DEFAULT_STEAMCMD_URL: &str =
+    "https://steamcdn-a.akamaihd.net/client/installer/steamcmd_osx.tar.gz";
+#[cfg(not(any(windows, target_os = "macos")))]
+pub const DEFAULT_STEAMCMD_URL: &str =
+    "https://steamcdn-a.akamaihd.net/client/installer/steamcmd_linux.tar.gz";
+
+/// Downloads and sets up SteamCMD in a directory of our own.
+#[derive(Debug, Clone)]
+pub struct Installer {
+    home_dir: PathBuf,
+    url: String,
+}
+
+impl Installer {
+    pub fn new(home_dir: PathBuf) -> Installer {
+        Installer {
+            home_dir,
+            url: DEFAULT_STEAMCMD_URL.to_string(),
+        }
+    }
+
+    /// Fetches the archive from `url` instead of Valve's CDN.
+    ///
+    /// Archives ending in `.zip` are unpacked as zip, anything else as `.tar.gz`.
+    pub fn with_url(mut self, url: impl Into<String>) -> Installer {
+        self.url = url.into();
+        self
+    }
+
+    /// Fetches and unpacks the archive, lets SteamCMD update itself, then checks it starts.
+    ///
+    /// Output of both SteamCMD runs is forwarded to `events`, if given.
+    pub async fn install(&self, events: Option<&EventSender>) -> Result<SteamCMD, Error> {
+        let archive = reqwest::get(&self.url)
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(Error::Fetch)?
+            .bytes()
+            .await
+            .map_err(Error::Fetch)?;
+
+        let home_dir = self.home_dir.clone();
+        let is_zip = self.url.ends_with(".zip");
+        tokio::task::spawn_blocking(move || unpack(&archive, &home_dir, is_zip))
+            .await
+            .map_err(|e| Error::Unpack(std::io::Error::other(e)))?
+            .map_err(Error::Unpack)?;
+
+        let steamcmd = SteamCMD::new(self.home_dir.clone());
+        // The first run only updates SteamCMD itself and its exit status is unreliable.
+        steamcmd.run_script("quit", events, None).await?;
+
+        let output = steamcmd.run_script("quit", events, None).await?;
+        if !output.success {
+            return Err(Error::Verify(output.stderr.trim().to_string()));
+        }
+        Ok(steamcmd)
+    }
+}
+
+fn unpack(archive: &[u8], dir: &Path, is_zip: bool) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    if is_zip {
+        zip::ZipArchive::new(Cursor::new(archive))
+            .and_then(|mut zip| zip.extract(dir))
+            .map_err(std::io::Error::other)
+    } else {
+        tar::Archive::new(GzDecoder::new(archive)).unpack(dir)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use tempfile::TempDir;
+
+    fn fake_steamcmd_archive() -> Vec<u8> {
+        let script = b"#!/bin/sh\nexit 0\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(script.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        builder
+            .append_data(&mut header, "steamcmd.sh", &script[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_install_from_local_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/steamcmd_linux.tar.gz")
+            .with_body(fake_steamcmd_archive())
+            .create_async()
+            .await;
+
+        let home = TempDir::new().unwrap();
+        let steamcmd = Installer::new(home.path().join("steamcmd"))
+            .with_url(format!("{}/steamcmd_linux.tar.gz", server.url()))
+            .install(None)
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(
+            steamcmd.steamcmd_full_path().unwrap(),
+            home.path().join("steamcmd").join("steamcmd.sh")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_install_reports_http_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/missing.tar.gz")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let home = TempDir::new().unwrap();
+        let result = Installer::new(home.path().to_path_buf())
+            .with_url(format!("{}/missing.tar.gz", server.url()))
+            .install(None)
+            .await;
+
+        assert!(matches!(result, Err(Error::Fetch(_))));
+    }
+}
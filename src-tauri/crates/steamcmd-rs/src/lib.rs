@@ -1,32 +1,95 @@
+mod cache;
+mod credentials;
+mod error;
+mod event;
+#[cfg(any(test, feature = "fake"))]
+pub mod fake;
+mod install;
+mod report;
+mod retry;
+mod runner;
+mod verify;
+
+pub use cache::{CacheEntry, CacheState, PruneOptions, PruneReport};
+pub use credentials::Credentials;
+pub use error::Error;
+pub use event::{EventSender, SteamCmdEvent};
+pub use install::{DEFAULT_STEAMCMD_URL, Installer};
+pub use report::{Attempt, DownloadReport, ItemResult, ScriptOutput};
+pub use retry::RetryPolicy;
+use runner::OutputLines;
+pub use runner::{OutputStream, Process, Runner, Spawned, SystemRunner};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
 use tempfile::NamedTempFile;
+use tokio::io::AsyncReadExt;
+pub use tokio_util::sync::CancellationToken;
+pub use verify::{Integrity, ItemIntegrity, VerifyReport};
 
-const STEAMCMD_NAME: &str = "steamcmd.exe";
+/// Launcher names probed inside the SteamCMD home, in order of preference.
+#[cfg(windows)]
+const STEAMCMD_CANDIDATES: &[&str] = &["steamcmd.exe"];
+#[cfg(not(windows))]
+const STEAMCMD_CANDIDATES: &[&str] = &["steamcmd.sh", "steamcmd"];
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct SteamCMD {
     home_dir: Option<PathBuf>,
+    credentials: Option<Credentials>,
+    retry: RetryPolicy,
+    runner: Arc<dyn Runner>,
+}
+
+impl Default for SteamCMD {
+    fn default() -> SteamCMD {
+        SteamCMD {
+            home_dir: None,
+            credentials: None,
+            retry: RetryPolicy::default(),
+            runner: Arc::new(SystemRunner),
+        }
+    }
 }
 
 impl SteamCMD {
-    const WORKSHOP_DIR_BASE: &'static str = r"steamapps\workshop\content";
+    const WORKSHOP_DIR_BASE: [&'static str; 3] = ["steamapps", "workshop", "content"];
+    const WORKSHOP_DOWNLOAD_DIR_BASE: [&'static str; 3] = ["steamapps", "workshop", "downloads"];
 
     pub fn new(home_dir: PathBuf) -> SteamCMD {
         SteamCMD {
             home_dir: Some(home_dir),
+            ..SteamCMD::default()
         }
     }
 
-    pub fn workshop_item_dir(&self, app_id: u64, mod_id: u64) -> Result<PathBuf, String> {
-        let base = self
-            .home_dir
-            .as_ref()
-            .ok_or("SteamCMD home directory not set")?;
-        Ok(base
-            .join(Self::WORKSHOP_DIR_BASE)
-            .join(app_id.to_string())
-            .join(mod_id.to_string()))
+    fn home_dir(&self) -> Result<&PathBuf, Error> {
+        self.home_dir.as_ref().ok_or(Error::HomeNotSet)
+    }
+
+    pub fn workshop_item_dir(&self, app_id: u64, mod_id: u64) -> Result<PathBuf, Error> {
+        let mut dir = self.home_dir()?.clone();
+        dir.extend(Self::WORKSHOP_DIR_BASE);
+        dir.push(app_id.to_string());
+        dir.push(mod_id.to_string());
+        Ok(dir)
+    }
+
+    /// Staging folder SteamCMD writes an item into while it is still downloading.
+    pub fn workshop_download_dir(&self, app_id: u64, mod_id: u64) -> Result<PathBuf, Error> {
+        let mut dir = self.home_dir()?.clone();
+        dir.extend(Self::WORKSHOP_DOWNLOAD_DIR_BASE);
+        dir.push(app_id.to_string());
+        dir.push(mod_id.to_string());
+        Ok(dir)
+    }
+
+    /// Whether the item's workshop folder exists and is not empty.
+    pub fn is_item_downloaded(&self, app_id: u64, mod_id: u64) -> bool {
+        self.workshop_item_dir(app_id, mod_id)
+            .map(|dir| report::is_populated(&dir))
+            .unwrap_or(false)
     }
 
     pub fn set_steamcmd_home(&mut self, path: PathBuf) -> &mut Self {
@@ -34,107 +97,560 @@ impl SteamCMD {
         self
     }
 
-    fn steamcmd_full_path(&self) -> Result<PathBuf, String> {
-        let base = self
-            .home_dir
-            .as_ref()
-            .ok_or("SteamCMD home directory not set")?;
-        Ok(base.join(STEAMCMD_NAME))
+    /// Sets the account used by every script run, `None` logs in anonymously.
+    pub fn set_credentials(&mut self, credentials: Option<Credentials>) -> &mut Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// Sets how items that failed to download are retried by [`SteamCMD::download_mod_par`].
+    pub fn set_retry_policy(&mut self, retry: RetryPolicy) -> &mut Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Replaces how SteamCMD is located and started, [`SystemRunner`] by default.
+    pub fn set_runner(&mut self, runner: Arc<dyn Runner>) -> &mut Self {
+        self.runner = runner;
+        self
+    }
+
+    /// Resolves the SteamCMD launcher through the configured [`Runner`].
+    ///
+    /// With [`SystemRunner`] this probes `steamcmd.exe` on Windows and `steamcmd.sh` /
+    /// `steamcmd` elsewhere, see [`Error::ExecutableNotFound`].
+    pub fn steamcmd_full_path(&self) -> Result<PathBuf, Error> {
+        self.runner.executable(self.home_dir()?)
     }
 
-    pub async fn run_script_from_path(&self, script: &PathBuf) -> Result<(), String> {
+    /// Runs a SteamCMD script, reading stdout line by line as it is produced.
+    ///
+    /// Every line is parsed into a [`SteamCmdEvent`] and forwarded to `events`, if given.
+    /// A non-zero exit status is reported through [`ScriptOutput::success`], not as an error.
+    /// Cancelling `cancel` kills the process and sets [`ScriptOutput::cancelled`].
+    pub async fn run_script_from_path(
+        &self,
+        script: &Path,
+        events: Option<&EventSender>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<ScriptOutput, Error> {
         let steamcmd_path = self.steamcmd_full_path()?;
-        let output = tokio::process::Command::new(steamcmd_path)
-            .arg("+runscript")
-            .arg(script)
-            .output()
-            .await
-            .map_err(|e| format!("Failed to run steamcmd: {}", e))?;
-
-        if output.status.success() {
-            Ok(())
-        } else {
-            Err(format!(
-                "SteamCMD failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            ))
+        let Spawned {
+            pid,
+            stdout,
+            mut stderr,
+            process: mut child,
+        } = self.runner.spawn(&steamcmd_path, script)?;
+
+        if let (Some(tx), Some(pid)) = (events, pid) {
+            let _ = tx.send(SteamCmdEvent::ProcessStarted { pid });
         }
+
+        let read_stderr = tokio::spawn(async move {
+            let mut buf = String::new();
+            stderr.read_to_string(&mut buf).await.map(|_| buf)
+        });
+
+        let cancelled = cancelled(cancel);
+        tokio::pin!(cancelled);
+
+        let mut parsed = Vec::new();
+        let mut error = None;
+        let mut was_cancelled = false;
+        let mut lines = OutputLines::new(stdout);
+        loop {
+            let line = tokio::select! {
+                line = lines.next_line() => line,
+                _ = &mut cancelled => {
+                    was_cancelled = true;
+                    break;
+                }
+            };
+            let line = match line {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(e) => {
+                    error = Some(Error::Spawn(e));
+                    break;
+                }
+            };
+            let event = SteamCmdEvent::parse(&line);
+            if let Some(tx) = events {
+                // A dropped receiver only means nobody is listening anymore.
+                let _ = tx.send(event.clone());
+            }
+            error = self.login_error(&event);
+            parsed.push(event);
+            if error.is_some() {
+                // SteamCMD keeps prompting after a failed login, stop it instead of waiting.
+                break;
+            }
+        }
+
+        if was_cancelled || error.is_some() {
+            let _ = child.kill().await;
+        }
+        if let Some(error) = error {
+            return Err(error);
+        }
+
+        let stderr = match read_stderr.await.map_err(std::io::Error::other) {
+            Ok(Ok(stderr)) => stderr,
+            Ok(Err(e)) | Err(e) => {
+                let _ = child.kill().await;
+                return Err(Error::Spawn(e));
+            }
+        };
+        let success = child.wait().await.map_err(Error::Spawn)?;
+
+        Ok(ScriptOutput {
+            events: parsed,
+            success: success && !was_cancelled,
+            stderr,
+            cancelled: was_cancelled,
+        })
     }
 
-    pub async fn run_script(&self, script: &str) -> Result<(), String> {
-        let mut file =
-            NamedTempFile::new().map_err(|e| format!("Failed to create temp file: {}", e))?;
-        file.write_all(script.as_bytes())
-            .map_err(|e| format!("Failed to write script: {}", e))?;
-        self.run_script_from_path(&file.path().to_path_buf()).await
+    /// Maps a failed login reported by SteamCMD to the matching [`Error`].
+    fn login_error(&self, event: &SteamCmdEvent) -> Option<Error> {
+        match event {
+            SteamCmdEvent::LoginFailed { user, reason } => {
+                Some(Error::login_failure(user.clone(), reason.clone()))
+            }
+            SteamCmdEvent::SteamGuardRequired => Some(Error::SteamGuardRequired {
+                user: self
+                    .credentials
+                    .as_ref()
+                    .map(|c| c.username.clone())
+                    .unwrap_or_else(|| "anonymous".to_string()),
+            }),
+            _ => None,
+        }
     }
 
-    fn download_item(game_id: u64, mod_id: u64) -> String {
-        format!("workshop_download_item {} {}", game_id, mod_id)
+    pub async fn run_script(
+        &self,
+        script: &str,
+        events: Option<&EventSender>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<ScriptOutput, Error> {
+        let mut file = NamedTempFile::new().map_err(Error::Script)?;
+        file.write_all(script.as_bytes()).map_err(Error::Script)?;
+        self.run_script_from_path(file.path(), events, cancel).await
     }
 
-    fn login(username: Option<&str>, password: Option<&str>) -> String {
-        let mut s = String::from("+login anonymous");
-        if let Some(u) = username {
-            s = format!("+login {} {}", u, password.unwrap_or(""));
+    fn download_item(game_id: u64, mod_id: u64, validate: bool) -> String {
+        let mut command = format!("workshop_download_item {} {}", game_id, mod_id);
+        if validate {
+            command += " validate";
         }
-        s
-    }
-
-    pub async fn download_mod(&self, game_id: u64, mod_ids: Vec<u64>) -> Result<(), String> {
-        let script = {
-            let mut script = String::new();
-            script += &SteamCMD::login(None, None);
-            script += "\n";
-            script += &mod_ids
-                .iter()
-                .map(|mod_id| SteamCMD::download_item(game_id, *mod_id))
-                .collect::<Vec<String>>()
-                .join("\n");
-            script += "\n";
-            script += "quit";
-            script
-        };
-        self.run_script(&script).await
+        command
+    }
+
+    fn download_script(&self, game_id: u64, mod_ids: &[u64], validate: bool) -> String {
+        let mut script = String::new();
+        script += &Credentials::login_command(self.credentials.as_ref());
+        script += "\n";
+        script += &mod_ids
+            .iter()
+            .map(|mod_id| SteamCMD::download_item(game_id, *mod_id, validate))
+            .collect::<Vec<String>>()
+            .join("\n");
+        script += "\n";
+        script += "quit";
+        script
+    }
+
+    /// Downloads the items sequentially in a single SteamCMD session.
+    pub async fn download_mod(
+        &self,
+        game_id: u64,
+        mod_ids: Vec<u64>,
+        events: Option<&EventSender>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<DownloadReport, Error> {
+        self.download_mod_par(game_id, mod_ids, 1, events, cancel)
+            .await
     }
 
+    /// Downloads the items using up to `n` SteamCMD processes in parallel.
+    ///
+    /// Returns a per-item [`DownloadReport`]; failing to start SteamCMD or to log in
+    /// (see [`Error::SteamGuardRequired`] and [`Error::InvalidPassword`]) are errors.
+    /// Items that fail are downloaded again according to the [`RetryPolicy`], and every
+    /// attempt is listed in [`ItemResult::attempts`].
+    ///
+    /// When `cancel` fires every process is killed, the partially downloaded items are
+    /// removed and [`Error::Cancelled`] is returned.
     pub async fn download_mod_par(
         &self,
         game_id: u64,
         mod_ids: Vec<u64>,
         n: usize,
-    ) -> Result<(), String> {
+        events: Option<&EventSender>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<DownloadReport, Error> {
+        self.fetch_items(game_id, mod_ids, n, false, events, cancel)
+            .await
+    }
+
+    /// Downloads the items again with `validate`, so SteamCMD checks every file against
+    /// the workshop manifest and replaces the ones that differ.
+    ///
+    /// Behaves like [`SteamCMD::download_mod_par`] otherwise.
+    pub async fn validate_mod_par(
+        &self,
+        game_id: u64,
+        mod_ids: Vec<u64>,
+        n: usize,
+        events: Option<&EventSender>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<DownloadReport, Error> {
+        self.fetch_items(game_id, mod_ids, n, true, events, cancel)
+            .await
+    }
+
+    async fn fetch_items(
+        &self,
+        game_id: u64,
+        mod_ids: Vec<u64>,
+        n: usize,
+        validate: bool,
+        events: Option<&EventSender>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<DownloadReport, Error> {
+        if mod_ids.is_empty() {
+            return Ok(DownloadReport::default());
+        }
+        self.steamcmd_full_path()?;
+
+        let mut report = DownloadReport::default();
+        let mut pending = mod_ids;
+        for attempt in 1..=self.retry.max_retries.saturating_add(1) {
+            if attempt > 1 {
+                let delay = self.retry.delay(attempt - 1);
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = cancelled(cancel) => return Err(Error::Cancelled),
+                }
+            }
+
+            let started = SystemTime::now();
+            let outputs = self
+                .download_batch(game_id, &pending, n, validate, events, cancel)
+                .await?;
+            if outputs.iter().any(|output| output.cancelled) {
+                self.remove_partial_items(game_id, &pending, &outputs);
+                return Err(Error::Cancelled);
+            }
+
+            let batch = DownloadReport::build(&pending, &outputs, started, |item_id| {
+                self.workshop_item_dir(game_id, item_id).ok()
+            });
+            report.record(attempt, batch);
+            pending = report.retryable();
+            if pending.is_empty() {
+                break;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Runs one download pass over `mod_ids`, split across up to `n` processes.
+    async fn download_batch(
+        &self,
+        game_id: u64,
+        mod_ids: &[u64],
+        n: usize,
+        validate: bool,
+        events: Option<&EventSender>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<Vec<ScriptOutput>, Error> {
         let n = n.min(mod_ids.len()).max(1);
-        let chunk_size = (mod_ids.len() + n - 1) / n;
-        let scripts = mod_ids
+        let chunk_size = mod_ids.len().div_ceil(n);
+        let fut = mod_ids
             .chunks(chunk_size)
-            .map(|mod_ids| {
-                let mut script = String::new();
-                script += &SteamCMD::login(None, None);
-                script += "\n";
-                script += &mod_ids
-                    .iter()
-                    .map(|mod_id| SteamCMD::download_item(game_id, *mod_id))
-                    .collect::<Vec<String>>()
-                    .join("\n");
-                script += "\n";
-                script += "quit";
-                script
-            })
+            .map(|chunk| self.download_script(game_id, chunk, validate))
+            .map(|script| async move { self.run_script(script.as_str(), events, cancel).await })
             .collect::<Vec<_>>();
 
-        let fut = scripts
+        futures::future::join_all(fut)
+            .await
             .into_iter()
-            .map(|script| async move { self.run_script(script.as_str()).await })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    /// Removes what a cancelled run left behind.
+    ///
+    /// Staging folders are always removed; content folders only for items whose download
+    /// started but never finished, so previously installed versions of untouched items survive.
+    fn remove_partial_items(&self, game_id: u64, mod_ids: &[u64], outputs: &[ScriptOutput]) {
+        let events = || outputs.iter().flat_map(|output| output.events.iter());
+        for &item_id in mod_ids {
+            let started = events().any(|event| {
+                matches!(event, SteamCmdEvent::DownloadStarted { item_id: id } if *id == item_id)
+            });
+            let finished = events().any(|event| {
+                matches!(event, SteamCmdEvent::DownloadSucceeded { item_id: id, .. } if *id == item_id)
+            });
+
+            let mut dirs = vec![self.workshop_download_dir(game_id, item_id)];
+            if started && !finished {
+                dirs.push(self.workshop_item_dir(game_id, item_id));
+            }
+            dirs.into_iter()
+                .flatten()
+                .filter(|dir| dir.exists())
+                .for_each(|dir| {
+                    let _ = std::fs::remove_dir_all(dir);
+                });
+        }
+    }
+}
+
+/// Resolves once `cancel` fires, or never when there is no token.
+async fn cancelled(cancel: Option<&CancellationToken>) {
+    match cancel {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake::{FakeLogin, FakeOutcome, FakeSteamCmd};
+    use std::time::Duration;
+    use tempfile::TempDir;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn fake_steamcmd(home: &TempDir, fake: &FakeSteamCmd) -> SteamCMD {
+        let mut steamcmd = SteamCMD::new(home.path().to_path_buf());
+        steamcmd.set_runner(Arc::new(fake.clone()));
+        steamcmd
+    }
+
+    #[tokio::test]
+    async fn test_fake_download_streams_events() {
+        let home = TempDir::new().unwrap();
+        let fake = FakeSteamCmd::new().with_item(2, [FakeOutcome::fail("File Not Found")]);
+        let steamcmd = fake_steamcmd(&home, &fake);
 
-        if futures::future::join_all(fut)
+        let (tx, mut rx) = unbounded_channel();
+        let report = steamcmd
+            .download_mod_par(602960, vec![1, 2, 3], 2, Some(&tx), None)
             .await
-            .into_iter()
-            .all(|x| x.is_ok())
-        {
-            return Ok(());
+            .unwrap();
+        drop(tx);
+
+        assert_eq!(report.succeeded(), vec![1, 3]);
+        assert_eq!(report.items[1].reason.as_deref(), Some("File Not Found"));
+        assert!(steamcmd.is_item_downloaded(602960, 3));
+        assert_eq!(fake.scripts().len(), 2);
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
         }
-        Err("Failed to download some of the mods".to_string())
+        assert!(events.contains(&SteamCmdEvent::LoggedIn {
+            user: "anonymous".to_string()
+        }));
+        assert!(events.contains(&SteamCmdEvent::DownloadFailed {
+            item_id: 2,
+            reason: "File Not Found".to_string()
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_fake_retries_only_failed_items() {
+        let home = TempDir::new().unwrap();
+        let fake = FakeSteamCmd::new()
+            .with_item(2, [FakeOutcome::fail("Timeout"), FakeOutcome::succeed()]);
+        let mut steamcmd = fake_steamcmd(&home, &fake);
+        steamcmd.set_retry_policy(RetryPolicy::new(2, Duration::ZERO));
+
+        let report = steamcmd
+            .download_mod(602960, vec![1, 2], None, None)
+            .await
+            .unwrap();
+
+        assert!(report.is_complete());
+        assert_eq!(report.items[0].attempts.len(), 1);
+        assert_eq!(report.items[1].attempts.len(), 2);
+        let scripts = fake.scripts();
+        assert_eq!(scripts.len(), 2);
+        assert!(!scripts[1].contains("602960 1"));
+        assert!(scripts[1].contains("602960 2"));
+    }
+
+    #[tokio::test]
+    async fn test_fake_cancel_removes_partial_items() {
+        let home = TempDir::new().unwrap();
+        let fake = FakeSteamCmd::new().with_item(2, [FakeOutcome::Hang]);
+        let steamcmd = fake_steamcmd(&home, &fake);
+
+        let cancel = CancellationToken::new();
+        let (tx, mut rx) = unbounded_channel();
+        let canceller = async {
+            while let Some(event) = rx.recv().await {
+                if event == (SteamCmdEvent::DownloadStarted { item_id: 2 }) {
+                    cancel.cancel();
+                }
+            }
+        };
+        let download = async {
+            let result = steamcmd
+                .download_mod(602960, vec![1, 2], Some(&tx), Some(&cancel))
+                .await;
+            drop(tx);
+            result
+        };
+        let (result, _) = tokio::join!(download, canceller);
+
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert!(steamcmd.is_item_downloaded(602960, 1));
+        assert!(!steamcmd.workshop_item_dir(602960, 2).unwrap().exists());
+    }
+
+    #[tokio::test]
+    async fn test_fake_login_failures() {
+        let home = TempDir::new().unwrap();
+        let credentials = Credentials::from_config(Some("alice"), Some("wrong"));
+
+        let fake = FakeSteamCmd::new().with_login(FakeLogin::Fail("Invalid Password".into()));
+        let mut steamcmd = fake_steamcmd(&home, &fake);
+        steamcmd.set_credentials(credentials.clone());
+        let result = steamcmd.download_mod(602960, vec![1], None, None).await;
+        assert!(matches!(result, Err(Error::InvalidPassword { user }) if user == "alice"));
+
+        let fake = FakeSteamCmd::new().with_login(FakeLogin::SteamGuard);
+        let mut steamcmd = fake_steamcmd(&home, &fake);
+        steamcmd.set_credentials(credentials);
+        let result = steamcmd.download_mod(602960, vec![1], None, None).await;
+        assert!(matches!(result, Err(Error::SteamGuardRequired { .. })));
+    }
+
+    #[test]
+    fn test_workshop_item_dir_components() {
+        let steamcmd = SteamCMD::new(PathBuf::from("home"));
+        let dir = steamcmd.workshop_item_dir(602960, 42).unwrap();
+        let components: Vec<_> = dir
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            components,
+            vec!["home", "steamapps", "workshop", "content", "602960", "42"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_platform_launcher() {
+        let home = TempDir::new().unwrap();
+        let launcher = home.path().join(STEAMCMD_CANDIDATES[0]);
+        std::fs::write(&launcher, b"").unwrap();
+
+        let steamcmd = SteamCMD::new(home.path().to_path_buf());
+        assert_eq!(steamcmd.steamcmd_full_path().unwrap(), launcher);
+    }
+
+    #[test]
+    fn test_missing_launcher_lists_candidates() {
+        let home = TempDir::new().unwrap();
+        let steamcmd = SteamCMD::new(home.path().to_path_buf());
+
+        match steamcmd.steamcmd_full_path() {
+            Err(Error::ExecutableNotFound { tried }) => {
+                assert_eq!(tried.len(), STEAMCMD_CANDIDATES.len());
+                assert!(tried.iter().all(|p| p.starts_with(home.path())));
+            }
+            other => panic!("Expected ExecutableNotFound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_download_script_uses_credentials() {
+        let mut steamcmd = SteamCMD::new(PathBuf::from("home"));
+        assert_eq!(
+            steamcmd.download_script(602960, &[1, 2], false),
+            "+login anonymous\nworkshop_download_item 602960 1\nworkshop_download_item 602960 2\nquit"
+        );
+
+        steamcmd.set_credentials(Credentials::from_config(Some("alice"), None));
+        assert!(
+            steamcmd
+                .download_script(602960, &[1], true)
+                .starts_with("+login alice\nworkshop_download_item 602960 1 validate")
+        );
+    }
+
+    #[test]
+    fn test_login_errors_are_distinct() {
+        let steamcmd = SteamCMD::default();
+        let failed = |reason: &str| SteamCmdEvent::LoginFailed {
+            user: "alice".to_string(),
+            reason: reason.to_string(),
+        };
+        assert!(matches!(
+            steamcmd.login_error(&failed("Invalid Password")),
+            Some(Error::InvalidPassword { .. })
+        ));
+        assert!(matches!(
+            steamcmd.login_error(&failed("Account Logon Denied")),
+            Some(Error::SteamGuardRequired { .. })
+        ));
+        assert!(matches!(
+            steamcmd.login_error(&SteamCmdEvent::SteamGuardRequired),
+            Some(Error::SteamGuardRequired { .. })
+        ));
+        assert!(matches!(
+            steamcmd.login_error(&failed("Rate Limit Exceeded")),
+            Some(Error::LoginFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_remove_partial_items() {
+        let home = TempDir::new().unwrap();
+        let steamcmd = SteamCMD::new(home.path().to_path_buf());
+        for id in [1, 2, 3] {
+            let content = steamcmd.workshop_item_dir(602960, id).unwrap();
+            std::fs::create_dir_all(&content).unwrap();
+            std::fs::write(content.join("filelist.xml"), b"x").unwrap();
+            std::fs::create_dir_all(steamcmd.workshop_download_dir(602960, id).unwrap()).unwrap();
+        }
+
+        let outputs = vec![ScriptOutput {
+            events: vec![
+                SteamCmdEvent::DownloadStarted { item_id: 1 },
+                SteamCmdEvent::DownloadSucceeded {
+                    item_id: 1,
+                    path: PathBuf::new(),
+                    bytes: 1,
+                },
+                SteamCmdEvent::DownloadStarted { item_id: 2 },
+            ],
+            success: false,
+            stderr: String::new(),
+            cancelled: true,
+        }];
+        steamcmd.remove_partial_items(602960, &[1, 2, 3], &outputs);
+
+        assert!(steamcmd.is_item_downloaded(602960, 1));
+        assert!(!steamcmd.is_item_downloaded(602960, 2));
+        assert!(steamcmd.is_item_downloaded(602960, 3));
+        for id in [1, 2, 3] {
+            assert!(!steamcmd.workshop_download_dir(602960, id).unwrap().exists());
+        }
+    }
+
+    #[test]
+    fn test_home_not_set() {
+        let steamcmd = SteamCMD::default();
+        assert!(matches!(
+            steamcmd.workshop_item_dir(1, 2),
+            Err(Error::HomeNotSet)
+        ));
     }
 }
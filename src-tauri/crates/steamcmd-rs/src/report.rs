@@ -0,0 +1,384 @@
+use crate::SteamCmdEvent;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Collected result of a single SteamCMD invocation.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptOutput {
+    /// Every line of stdout, parsed in order.
+    pub events: Vec<SteamCmdEvent>,
+    /// Whether the process exited with status 0. SteamCMD often does even on failure.
+    pub success: bool,
+    /// Everything written to stderr.
+    pub stderr: String,
+    /// Whether the process was killed because the run was cancelled.
+    pub cancelled: bool,
+}
+
+/// Failure reasons that another attempt will not fix.
+const PERMANENT_FAILURES: [&str; 3] = ["File Not Found", "Access Denied", "No Subscription"];
+
+/// Outcome of one attempt at downloading an item.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attempt {
+    /// 1 for the initial download, 2 for the first retry, and so on.
+    pub attempt: u32,
+    pub success: bool,
+    pub reason: Option<String>,
+    pub bytes: Option<u64>,
+}
+
+/// Outcome of downloading a single workshop item.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemResult {
+    pub item_id: u64,
+    pub success: bool,
+    /// Why the download failed, if it did.
+    pub reason: Option<String>,
+    /// Where the item was downloaded to.
+    pub path: Option<PathBuf>,
+    /// Size of the downloaded item in bytes.
+    pub bytes: Option<u64>,
+    /// Every attempt made for this item, the last one decided the fields above.
+    pub attempts: Vec<Attempt>,
+}
+
+/// Per-item report of a download run, in the order the items were requested.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadReport {
+    pub items: Vec<ItemResult>,
+}
+
+impl DownloadReport {
+    /// Builds a report from SteamCMD's output, cross-checked against the item folders on disk.
+    ///
+    /// An item only counts as downloaded when its folder exists and is not empty and
+    /// SteamCMD did not report it as failed, regardless of the process exit status.
+    /// Without a success event the folder must also have been written to since
+    /// `started`, so a folder left over from an earlier install is not mistaken for
+    /// the result of a crashed run.
+    pub(crate) fn build(
+        item_ids: &[u64],
+        outputs: &[ScriptOutput],
+        started: SystemTime,
+        item_dir: impl Fn(u64) -> Option<PathBuf>,
+    ) -> DownloadReport {
+        let mut last_event: HashMap<u64, &SteamCmdEvent> = HashMap::new();
+        outputs
+            .iter()
+            .flat_map(|output| output.events.iter())
+            .filter(|event| {
+                matches!(
+                    event,
+                    SteamCmdEvent::DownloadSucceeded { .. } | SteamCmdEvent::DownloadFailed { .. }
+                )
+            })
+            .for_each(|event| {
+                if let Some(item_id) = event.item_id() {
+                    last_event.insert(item_id, event);
+                }
+            });
+
+        let process_error = outputs
+            .iter()
+            .find(|output| !output.success)
+            .map(|output| output.stderr.trim().to_string())
+            .filter(|stderr| !stderr.is_empty());
+
+        let items = item_ids
+            .iter()
+            .map(|&item_id| {
+                let on_disk = item_dir(item_id).filter(|dir| is_populated(dir));
+                match (last_event.get(&item_id), on_disk) {
+                    (Some(SteamCmdEvent::DownloadFailed { reason, .. }), _) => {
+                        ItemResult::failed(item_id, reason.clone())
+                    }
+                    (Some(SteamCmdEvent::DownloadSucceeded { .. }), None) => {
+                        ItemResult::failed(item_id, "Item folder missing after download".into())
+                    }
+                    (Some(SteamCmdEvent::DownloadSucceeded { path, bytes, .. }), Some(dir)) => {
+                        ItemResult::succeeded(
+                            item_id,
+                            path.clone(),
+                            Some(*bytes).filter(|b| *b > 0),
+                            &dir,
+                        )
+                    }
+                    (_, Some(dir)) if modified_since(&dir, started) => {
+                        ItemResult::succeeded(item_id, dir.clone(), None, &dir)
+                    }
+                    (_, _) => ItemResult::failed(
+                        item_id,
+                        process_error
+                            .clone()
+                            .unwrap_or_else(|| "No result reported by SteamCMD".into()),
+                    ),
+                }
+            })
+            .collect();
+
+        DownloadReport { items }
+    }
+
+    /// IDs of the items that were downloaded successfully.
+    pub fn succeeded(&self) -> Vec<u64> {
+        self.items
+            .iter()
+            .filter(|item| item.success)
+            .map(|item| item.item_id)
+            .collect()
+    }
+
+    /// IDs of the failed items that are worth another attempt.
+    pub fn retryable(&self) -> Vec<u64> {
+        self.items
+            .iter()
+            .filter(|item| item.is_retryable())
+            .map(|item| item.item_id)
+            .collect()
+    }
+
+    /// Merges the results of download attempt number `attempt` into this report.
+    ///
+    /// Items already present are replaced by their newer result while keeping
+    /// their attempt history, new items are appended.
+    pub(crate) fn record(&mut self, attempt: u32, report: DownloadReport) {
+        for mut result in report.items {
+            let entry = Attempt {
+                attempt,
+                success: result.success,
+                reason: result.reason.clone(),
+                bytes: result.bytes,
+            };
+            match self
+                .items
+                .iter_mut()
+                .find(|item| item.item_id == result.item_id)
+            {
+                Some(existing) => {
+                    result.attempts = std::mem::take(&mut existing.attempts);
+                    result.attempts.push(entry);
+                    *existing = result;
+                }
+                None => {
+                    result.attempts.push(entry);
+                    self.items.push(result);
+                }
+            }
+        }
+    }
+
+    /// Results of the items that failed to download.
+    pub fn failed(&self) -> Vec<&ItemResult> {
+        self.items.iter().filter(|item| !item.success).collect()
+    }
+
+    /// Whether every requested item was downloaded.
+    pub fn is_complete(&self) -> bool {
+        self.items.iter().all(|item| item.success)
+    }
+}
+
+impl ItemResult {
+    /// Whether the item failed for a reason another attempt might fix.
+    pub fn is_retryable(&self) -> bool {
+        !self.success
+            && !self
+                .reason
+                .as_deref()
+                .is_some_and(|reason| PERMANENT_FAILURES.iter().any(|p| reason.contains(p)))
+    }
+
+    fn succeeded(item_id: u64, path: PathBuf, bytes: Option<u64>, dir: &Path) -> ItemResult {
+        ItemResult {
+            item_id,
+            success: true,
+            reason: None,
+            path: Some(path),
+            bytes: bytes.or_else(|| Some(fs_utils::directory_size(dir))),
+            attempts: Vec::new(),
+        }
+    }
+
+    fn failed(item_id: u64, reason: String) -> ItemResult {
+        ItemResult {
+            item_id,
+            success: false,
+            reason: Some(reason),
+            path: None,
+            bytes: None,
+            attempts: Vec::new(),
+        }
+    }
+}
+
+/// Whether `dir` exists and contains at least one entry.
+pub(crate) fn is_populated(dir: &Path) -> bool {
+    std::fs::read_dir(dir)
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false)
+}
+
+/// Whether `dir` or anything below it was modified at or after `since`.
+fn modified_since(dir: &Path, since: SystemTime) -> bool {
+    let modified = |path: &Path| {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified >= since)
+    };
+    if modified(dir) {
+        return true;
+    }
+    std::fs::read_dir(dir).is_ok_and(|entries| {
+        entries.flatten().any(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                modified_since(&path, since)
+            } else {
+                modified(&path)
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn output(events: Vec<SteamCmdEvent>) -> ScriptOutput {
+        ScriptOutput {
+            events,
+            success: true,
+            stderr: String::new(),
+            cancelled: false,
+        }
+    }
+
+    #[test]
+    fn test_report_checks_disk() {
+        let home = TempDir::new().unwrap();
+        let present = home.path().join("1");
+        std::fs::create_dir(&present).unwrap();
+        std::fs::write(present.join("filelist.xml"), b"<contentpackage/>").unwrap();
+
+        let outputs = vec![output(vec![
+            SteamCmdEvent::DownloadSucceeded {
+                item_id: 1,
+                path: present.clone(),
+                bytes: 17,
+            },
+            SteamCmdEvent::DownloadSucceeded {
+                item_id: 2,
+                path: home.path().join("2"),
+                bytes: 5,
+            },
+            SteamCmdEvent::DownloadFailed {
+                item_id: 3,
+                reason: "Timeout".into(),
+            },
+        ])];
+
+        let report = DownloadReport::build(&[1, 2, 3, 4], &outputs, SystemTime::UNIX_EPOCH, |id| {
+            Some(home.path().join(id.to_string()))
+        });
+
+        assert_eq!(report.succeeded(), vec![1]);
+        assert_eq!(report.items[0].bytes, Some(17));
+        assert_eq!(
+            report.items[1].reason.as_deref(),
+            Some("Item folder missing after download")
+        );
+        assert_eq!(report.items[2].reason.as_deref(), Some("Timeout"));
+        assert_eq!(
+            report.items[3].reason.as_deref(),
+            Some("No result reported by SteamCMD")
+        );
+        assert!(!report.is_complete());
+    }
+
+    #[test]
+    fn test_report_trusts_disk_over_exit_status() {
+        let started = SystemTime::now() - Duration::from_secs(1);
+        let home = TempDir::new().unwrap();
+        let present = home.path().join("7");
+        std::fs::create_dir(&present).unwrap();
+        std::fs::write(present.join("a.xml"), b"hello").unwrap();
+
+        let outputs = vec![ScriptOutput {
+            events: vec![],
+            success: false,
+            stderr: "crashed".into(),
+            cancelled: false,
+        }];
+        let report = DownloadReport::build(&[7, 8], &outputs, started, |id| {
+            Some(home.path().join(id.to_string()))
+        });
+
+        assert!(report.items[0].success);
+        assert_eq!(report.items[0].bytes, Some(5));
+        assert_eq!(report.items[1].reason.as_deref(), Some("crashed"));
+    }
+
+    #[test]
+    fn test_report_ignores_stale_folder() {
+        let home = TempDir::new().unwrap();
+        let stale = home.path().join("7");
+        std::fs::create_dir(&stale).unwrap();
+        std::fs::write(stale.join("a.xml"), b"hello").unwrap();
+
+        // The run started after the folder was last written
+        let started = SystemTime::now() + Duration::from_secs(60);
+        let outputs = vec![ScriptOutput {
+            events: vec![],
+            success: false,
+            stderr: "crashed".into(),
+            cancelled: false,
+        }];
+        let report = DownloadReport::build(&[7], &outputs, started, |id| {
+            Some(home.path().join(id.to_string()))
+        });
+
+        assert!(!report.items[0].success);
+        assert_eq!(report.items[0].reason.as_deref(), Some("crashed"));
+    }
+
+    #[test]
+    fn test_record_keeps_attempt_history() {
+        let first = DownloadReport {
+            items: vec![
+                ItemResult::failed(1, "Timeout".into()),
+                ItemResult::failed(2, "File Not Found".into()),
+            ],
+        };
+        let mut report = DownloadReport::default();
+        report.record(1, first);
+        assert_eq!(report.retryable(), vec![1]);
+
+        let retry = DownloadReport {
+            items: vec![ItemResult::succeeded(
+                1,
+                PathBuf::from("1"),
+                Some(3),
+                Path::new("1"),
+            )],
+        };
+        report.record(2, retry);
+
+        assert_eq!(report.succeeded(), vec![1]);
+        let attempts: Vec<_> = report.items[0]
+            .attempts
+            .iter()
+            .map(|a| (a.attempt, a.success))
+            .collect();
+        assert_eq!(attempts, vec![(1, false), (2, true)]);
+        assert_eq!(report.items[1].attempts.len(), 1);
+        assert!(report.retryable().is_empty());
+    }
+}
@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+/// How items that failed to download are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RetryPolicy {
+    /// How many more times a failed item is downloaded, 0 disables retries.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every further retry.
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32, backoff: Duration) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            backoff,
+        }
+    }
+
+    /// Delay before the `retry`-th retry, counting from 1.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 1u32 << retry.saturating_sub(1).min(16);
+        self.backoff.saturating_mul(factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exponential_delay() {
+        let policy = RetryPolicy::new(3, Duration::from_secs(5));
+        assert_eq!(policy.delay(1), Duration::from_secs(5));
+        assert_eq!(policy.delay(2), Duration::from_secs(10));
+        assert_eq!(policy.delay(3), Duration::from_secs(20));
+        assert_eq!(RetryPolicy::default().delay(4), Duration::ZERO);
+    }
+}
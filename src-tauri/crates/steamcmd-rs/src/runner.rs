@@ -0,0 +1,129 @@
+use crate::{Error, STEAMCMD_CANDIDATES, SteamCmdEvent};
+use futures::future::BoxFuture;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Output stream of a spawned SteamCMD process.
+pub type OutputStream = Box<dyn AsyncRead + Send + Unpin>;
+
+/// Handle to a running SteamCMD process.
+pub trait Process: Send {
+    /// Stops the process.
+    fn kill(&mut self) -> BoxFuture<'_, std::io::Result<()>>;
+    /// Waits for the process to exit, resolving to whether it exited successfully.
+    fn wait(&mut self) -> BoxFuture<'_, std::io::Result<bool>>;
+}
+
+/// A started SteamCMD process with its output streams taken apart.
+pub struct Spawned {
+    pub pid: Option<u32>,
+    pub stdout: OutputStream,
+    pub stderr: OutputStream,
+    pub process: Box<dyn Process>,
+}
+
+/// Splits SteamCMD stdout into lines.
+///
+/// SteamCMD prints its Steam Guard prompt without a newline and then waits for input,
+/// so a partial line is returned as soon as it reads as that prompt.
+pub(crate) struct OutputLines {
+    stream: OutputStream,
+    buf: Vec<u8>,
+}
+
+impl OutputLines {
+    pub(crate) fn new(stream: OutputStream) -> Self {
+        OutputLines {
+            stream,
+            buf: Vec::new(),
+        }
+    }
+
+    /// The next line without its line ending, `None` at the end of the stream.
+    ///
+    /// Cancel safe: dropping the future loses no output.
+    pub(crate) async fn next_line(&mut self) -> std::io::Result<Option<String>> {
+        loop {
+            if let Some(end) = self.buf.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buf.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                return Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()));
+            }
+            let partial = String::from_utf8_lossy(&self.buf).into_owned();
+            if SteamCmdEvent::parse(&partial) == SteamCmdEvent::SteamGuardRequired {
+                self.buf.clear();
+                return Ok(Some(partial));
+            }
+
+            let mut chunk = [0; 4096];
+            let read = self.stream.read(&mut chunk).await?;
+            if read == 0 {
+                self.buf.clear();
+                return Ok((!partial.is_empty()).then_some(partial));
+            }
+            self.buf.extend_from_slice(&chunk[..read]);
+        }
+    }
+}
+
+/// Locates and starts SteamCMD.
+///
+/// [`SystemRunner`] runs the real executable; tests swap in a fake through
+/// [`SteamCMD::set_runner`](crate::SteamCMD::set_runner).
+pub trait Runner: Send + Sync + Debug {
+    /// Resolves the launcher inside the SteamCMD home.
+    fn executable(&self, home: &Path) -> Result<PathBuf, Error>;
+    /// Starts `executable` running the script at `script`.
+    fn spawn(&self, executable: &Path, script: &Path) -> Result<Spawned, Error>;
+}
+
+/// Runs the SteamCMD launcher installed in the home directory.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemRunner;
+
+impl Runner for SystemRunner {
+    /// Probes `steamcmd.exe` on Windows and `steamcmd.sh` / `steamcmd` elsewhere,
+    /// returning [`Error::ExecutableNotFound`] with every candidate tried when none exists.
+    fn executable(&self, home: &Path) -> Result<PathBuf, Error> {
+        let tried: Vec<PathBuf> = STEAMCMD_CANDIDATES
+            .iter()
+            .map(|name| home.join(name))
+            .collect();
+        tried
+            .iter()
+            .find(|path| path.is_file())
+            .cloned()
+            .ok_or(Error::ExecutableNotFound { tried })
+    }
+
+    fn spawn(&self, executable: &Path, script: &Path) -> Result<Spawned, Error> {
+        let mut child = tokio::process::Command::new(executable)
+            .arg("+runscript")
+            .arg(script)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(Error::Spawn)?;
+
+        Ok(Spawned {
+            pid: child.id(),
+            stdout: Box::new(child.stdout.take().expect("stdout is piped")),
+            stderr: Box::new(child.stderr.take().expect("stderr is piped")),
+            process: Box::new(child),
+        })
+    }
+}
+
+impl Process for tokio::process::Child {
+    fn kill(&mut self) -> BoxFuture<'_, std::io::Result<()>> {
+        Box::pin(tokio::process::Child::kill(self))
+    }
+
+    fn wait(&mut self) -> BoxFuture<'_, std::io::Result<bool>> {
+        Box::pin(async move { Ok(tokio::process::Child::wait(self).await?.success()) })
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{CancellationToken, DownloadReport, Error, EventSender, SteamCMD};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Result of checking one item against its known-good hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Integrity {
+    /// The content already matched the expected hash.
+    Intact,
+    /// The content was damaged and validation restored it.
+    Repaired,
+    /// The content still does not match after validation.
+    Corrupted,
+    /// There is no expected hash to compare against, the item was only validated.
+    Unverified,
+}
+
+/// Verification outcome of a single workshop item.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemIntegrity {
+    pub item_id: u64,
+    pub status: Integrity,
+    /// The hash the content is held against, see [`ItemIntegrity::assess`].
+    pub expected_hash: Option<String>,
+    /// Hash of the content before validation, `None` if it was missing or unreadable.
+    pub hash_before: Option<String>,
+    /// Hash of the content after validation.
+    pub hash_after: Option<String>,
+    /// Why the item could not be repaired, if it could not.
+    pub reason: Option<String>,
+}
+
+impl ItemIntegrity {
+    /// Classifies an item from its hashes before and after validation.
+    ///
+    /// Validation brings the item to the version the Workshop serves, so when it
+    /// succeeds with a hash other than `expected_hash` the item was updated since that
+    /// hash was recorded. The hash after validation then becomes the expected one, and
+    /// the item is only repaired if validation changed it.
+    pub fn assess(
+        item_id: u64,
+        expected_hash: Option<String>,
+        hash_before: Option<String>,
+        hash_after: Option<String>,
+        failure: Option<String>,
+    ) -> ItemIntegrity {
+        let expected_hash = match expected_hash {
+            Some(expected)
+                if hash_before.as_ref() != Some(&expected)
+                    && failure.is_none()
+                    && hash_after.is_some() =>
+            {
+                hash_after.clone()
+            }
+            expected => expected,
+        };
+        let (status, reason) = match &expected_hash {
+            None => (Integrity::Unverified, failure),
+            Some(expected) if hash_before.as_ref() == Some(expected) => (Integrity::Intact, None),
+            Some(expected) if hash_after.as_ref() == Some(expected) => (Integrity::Repaired, None),
+            Some(_) => (
+                Integrity::Corrupted,
+                failure.or_else(|| Some("Content differs from the installed version".into())),
+            ),
+        };
+        ItemIntegrity {
+            item_id,
+            status,
+            expected_hash,
+            hash_before,
+            hash_after,
+            reason,
+        }
+    }
+}
+
+/// Per-item report of a verify/repair run, in the order the items were requested.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    pub items: Vec<ItemIntegrity>,
+}
+
+impl VerifyReport {
+    fn with_status(&self, status: Integrity) -> Vec<u64> {
+        self.items
+            .iter()
+            .filter(|item| item.status == status)
+            .map(|item| item.item_id)
+            .collect()
+    }
+
+    /// IDs of items that were damaged, whether or not they could be repaired.
+    pub fn corrupted(&self) -> Vec<u64> {
+        self.items
+            .iter()
+            .filter(|item| matches!(item.status, Integrity::Repaired | Integrity::Corrupted))
+            .map(|item| item.item_id)
+            .collect()
+    }
+
+    /// IDs of items that were damaged and have been restored.
+    pub fn repaired(&self) -> Vec<u64> {
+        self.with_status(Integrity::Repaired)
+    }
+
+    /// IDs of items that are still damaged.
+    pub fn unrepaired(&self) -> Vec<u64> {
+        self.with_status(Integrity::Corrupted)
+    }
+}
+
+impl SteamCMD {
+    /// Verifies workshop items against `expected` hashes and repairs the damaged ones.
+    ///
+    /// Every item is hashed with [`fs_utils::hash_directory`], validated through
+    /// [`SteamCMD::validate_mod_par`] and hashed again to tell intact, repaired and
+    /// still corrupted items apart.
+    pub async fn verify_items(
+        &self,
+        game_id: u64,
+        mod_ids: Vec<u64>,
+        expected: &HashMap<u64, String>,
+        n: usize,
+        events: Option<&EventSender>,
+        cancel: Option<&CancellationToken>,
+    ) -> Result<VerifyReport, Error> {
+        let hash = |item_id: u64| {
+            self.workshop_item_dir(game_id, item_id)
+                .ok()
+                .filter(|dir| dir.exists())
+                .and_then(|dir| fs_utils::hash_directory(dir).ok())
+        };
+
+        let before: Vec<Option<String>> = mod_ids.iter().map(|&id| hash(id)).collect();
+        let validation: DownloadReport = self
+            .validate_mod_par(game_id, mod_ids.clone(), n, events, cancel)
+            .await?;
+
+        let items = mod_ids
+            .iter()
+            .zip(before)
+            .map(|(&item_id, hash_before)| {
+                let failure = validation
+                    .items
+                    .iter()
+                    .find(|item| item.item_id == item_id)
+                    .and_then(|item| item.reason.clone());
+                ItemIntegrity::assess(
+                    item_id,
+                    expected.get(&item_id).cloned(),
+                    hash_before,
+                    hash(item_id),
+                    failure,
+                )
+            })
+            .collect();
+
+        Ok(VerifyReport { items })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn h(s: &str) -> Option<String> {
+        Some(s.to_string())
+    }
+
+    #[test]
+    fn test_assess() {
+        let intact = ItemIntegrity::assess(1, h("a"), h("a"), h("a"), None);
+        assert_eq!(intact.status, Integrity::Intact);
+
+        let repaired = ItemIntegrity::assess(2, h("a"), h("b"), h("a"), None);
+        assert_eq!(repaired.status, Integrity::Repaired);
+
+        let missing = ItemIntegrity::assess(3, h("a"), None, None, Some("Timeout".into()));
+        assert_eq!(missing.status, Integrity::Corrupted);
+        assert_eq!(missing.reason.as_deref(), Some("Timeout"));
+
+        let unknown = ItemIntegrity::assess(4, None, h("a"), h("a"), None);
+        assert_eq!(unknown.status, Integrity::Unverified);
+
+        // Updated on the Workshop since "a" was recorded
+        let updated = ItemIntegrity::assess(5, h("a"), h("b"), h("c"), None);
+        assert_eq!(updated.status, Integrity::Repaired);
+        assert_eq!(updated.expected_hash, h("c"));
+        let current = ItemIntegrity::assess(6, h("a"), h("c"), h("c"), None);
+        assert_eq!(current.status, Integrity::Intact);
+        assert_eq!(current.expected_hash, h("c"));
+
+        let report = VerifyReport {
+            items: vec![intact, repaired, missing, unknown],
+        };
+        assert_eq!(report.corrupted(), vec![2, 3]);
+        assert_eq!(report.repaired(), vec![2]);
+        assert_eq!(report.unrepaired(), vec![3]);
+    }
+}
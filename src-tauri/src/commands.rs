@@ -14,17 +14,36 @@ use std::fs;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use crate::build_info::BuildInfo;
-use crate::once::{BARO_MANAGER, STEAM_WORKSHOP_CLIENT, STEAMCMD_MANAGER};
-use constants::{BAROTRAUMA_GAME_ID, GLOBAL_CONFIG_FILE, ROAMING};
+use crate::jobs::JobInfo;
+use crate::once::{
+    BARO_MANAGER, DOWNLOAD_JOBS, PREVIEW_CACHE, STEAM_PLAYER_CLIENT, STEAM_QUERY_CLIENT,
+    STEAM_REQUEST_POLICY, STEAM_WORKSHOP_CLIENT, STEAMCMD_MANAGER, WORKSHOP_METADATA_CACHE,
+};
+use constants::{BAROTRAUMA_GAME_ID, GLOBAL_CONFIG_FILE, MANAGED_STEAMCMD_HOME, ROAMING};
 use futures::TryFutureExt;
 use futures::future::try_join_all;
-use imagen::{BackgroundConfig, process_background};
+use imagen::{BackgroundConfig, process_background, thumbnail_data_url};
 use logger::{debug, error, info, warn};
-use mod_analyzer::{BarotraumaMod, ModList, parse_dependencies};
-use steam_api::WorkshopItem;
+use mod_analyzer::{
+    BarotraumaMod, ContentConflict, ContentFile, DependencySource, GameCompatibility, HashCheck,
+    ModList, ModValidation, detect_content_conflicts, merge_dependencies, parse_dependencies,
+    retrieve_change_notes, retrieve_creator_profiles,
+};
+use steam_api::{
+    CacheMode, CachePolicy, ChangeNote, ItemValidation, QueryPage, RequestPolicy, Source,
+    WorkshopItem, WorkshopQuery,
+};
+use steamcmd_rs::{
+    CacheEntry, CancellationToken, Credentials, DownloadReport, Installer, Integrity, PruneOptions,
+    PruneReport, RetryPolicy, SteamCMD, SteamCmdEvent, VerifyReport,
+};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::RwLockWriteGuard;
+use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+use tokio::task::JoinHandle;
 
 /// Recursively copies a directory, overwriting existing files.
 fn copy_dir_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
@@ -63,6 +82,8 @@ pub struct MissingDependency {
     pub dependency_name: String,
     /// Steam Workshop ID of the missing dependency, if declared.
     pub dependency_steam_id: Option<u64>,
+    /// Whether the dependency is declared in content.xml, on the Workshop or both.
+    pub source: DependencySource,
 }
 
 /// Result of `detect_mod_conflicts`: lists missing dependencies among enabled mods.
@@ -70,6 +91,8 @@ pub struct MissingDependency {
 #[serde(rename_all = "camelCase")]
 pub struct ConflictReport {
     pub missing_dependencies: Vec<MissingDependency>,
+    /// Prefab identifiers defined by more than one enabled mod.
+    pub content_conflicts: Vec<ContentConflict>,
 }
 
 /// Path to the persistent hash cache file.
@@ -170,7 +193,46 @@ pub fn write_config(config: Config) -> Result<(), String> {
         .map_err(|e| format!("{}, failed to create config directory.", e))?;
     config
         .to_file(GLOBAL_CONFIG_FILE.clone())
-        .map_err(|e| format!("{}, failed to write config file.", e))
+        .map_err(|e| format!("{}, failed to write config file.", e))?;
+    WORKSHOP_METADATA_CACHE.set_policy(metadata_cache_policy(&config));
+    PREVIEW_CACHE.set_max_bytes(preview_cache_max_bytes(&config));
+    STEAM_REQUEST_POLICY.set(workshop_request_policy(&config));
+    Ok(())
+}
+
+/// The workshop metadata cache policy configured in `conf`.
+pub(crate) fn metadata_cache_policy(conf: &Config) -> CachePolicy {
+    let cache = conf.workshop_cache.unwrap_or_default();
+    let mode = if cache.offline {
+        CacheMode::Offline
+    } else if cache.stale_while_revalidate {
+        CacheMode::StaleWhileRevalidate
+    } else {
+        CacheMode::Refresh
+    };
+    CachePolicy {
+        ttl: Duration::from_secs(cache.ttl_secs),
+        mode,
+    }
+}
+
+/// The size limit of the preview thumbnail cache configured in `conf`.
+pub(crate) fn preview_cache_max_bytes(conf: &Config) -> u64 {
+    conf.preview_cache_max_mb.saturating_mul(1024 * 1024)
+}
+
+/// The Steam Web API request policy configured in `conf`.
+pub(crate) fn workshop_request_policy(conf: &Config) -> RequestPolicy {
+    let Some(requests) = conf.workshop_requests else {
+        return RequestPolicy::default();
+    };
+    let retry = requests.retry.unwrap_or_default();
+    RequestPolicy {
+        concurrency: (requests.concurrency as usize).max(1),
+        timeout: Duration::from_secs(requests.timeout_secs.max(1)),
+        max_retries: retry.max_retries as u32,
+        backoff: Duration::from_secs(retry.backoff_secs),
+    }
 }
 
 /// Reads the configuration from the global config file.
@@ -247,9 +309,59 @@ pub async fn retrieve_mod_metadata(
     mods: Vec<BarotraumaMod>,
     batch_size: usize,
 ) -> Result<Vec<BarotraumaMod>, String> {
-    get_mod_metadata(mods, batch_size, STEAM_WORKSHOP_CLIENT.read().await.deref())
+    let conf: Config = read_config()?;
+    let mut mods = get_mod_metadata(mods, batch_size, STEAM_WORKSHOP_CLIENT.read().await.deref())
         .map_err(|e| format!("{}, failed to retrieve mod metadata.", e))
-        .await
+        .await?;
+    resolve_creators(&mut mods, &conf).await;
+    Ok(mods)
+}
+
+/// Fills in the creator profiles of `mods` when a Steam Web API key is configured.
+///
+/// A failed lookup only costs the profiles, the mods are kept as they are.
+async fn resolve_creators(mods: &mut [BarotraumaMod], conf: &Config) {
+    if conf
+        .steam_api_key
+        .as_deref()
+        .is_none_or(|key| key.trim().is_empty())
+    {
+        debug!("No Steam Web API key configured, skipping creator profiles.");
+        return;
+    }
+    // Clones share the profile cache.
+    let mut client = STEAM_PLAYER_CLIENT.read().await.clone();
+    client.set_api_key(conf.steam_api_key.clone());
+    if let Err(e) = retrieve_creator_profiles(mods, &client).await {
+        warn!("{e}, creator profiles are unavailable.");
+    }
+}
+
+/// Lists every installed mod published by the Steam user `creator`, sorted by name.
+///
+/// `creator` is a Steam64 ID such as `CreatorProfile.steamId`; it is passed as a string
+/// because it does not fit a JavaScript number.
+#[tauri::command]
+pub async fn list_mods_by_creator(creator: String) -> Result<Vec<BarotraumaMod>, String> {
+    let creator = creator
+        .trim()
+        .parse::<u64>()
+        .map_err(|e| format!("{e}, invalid creator Steam ID."))?;
+    let conf: Config = read_config()?;
+    let installed = list_installed_mods().await?;
+    let mut mods = get_mod_metadata(
+        installed,
+        conf.metadata_retrieve_batchsize as usize,
+        STEAM_WORKSHOP_CLIENT.read().await.deref(),
+    )
+    .map_err(|e| format!("{}, failed to retrieve mod metadata.", e))
+    .await?
+    .into_iter()
+    .filter(|baro_mod| baro_mod.creator == Some(creator))
+    .collect::<Vec<BarotraumaMod>>();
+    resolve_creators(&mut mods, &conf).await;
+    mods.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(mods)
 }
 
 /// Lists all enabled Barotrauma mods found in the configured game directory.
@@ -268,26 +380,372 @@ pub async fn list_enabled_mods() -> Result<Vec<BarotraumaMod>, String> {
         .enabled_mods()
 }
 
+/// Event name under which per-item download progress is emitted to the frontend.
+pub const DOWNLOAD_PROGRESS_EVENT: &str = "download-progress";
+
+/// Event name under which download job state changes are emitted to the frontend.
+pub const DOWNLOAD_JOB_EVENT: &str = "download-job";
+
+/// How long `cancel_download` waits for a job to stop before giving up.
+const CANCEL_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn emit_job(app: &AppHandle, job: Option<JobInfo>) {
+    if let Some(job) = job
+        && let Err(e) = app.emit(DOWNLOAD_JOB_EVENT, &job)
+    {
+        warn!("Failed to emit download job update: {}", e);
+    }
+}
+
 /// Downloads the specified mods using SteamCMD.
+///
+/// The download runs as a job in [`DOWNLOAD_JOBS`], whose state changes are emitted as
+/// [`DOWNLOAD_JOB_EVENT`] events so it can be cancelled with `cancel_download`.
+/// Progress parsed from SteamCMD's output is forwarded to the frontend as
+/// [`DOWNLOAD_PROGRESS_EVENT`] events while the download is running, and the
+/// returned report tells which items actually arrived on disk.
+#[tauri::command]
+pub async fn download_mods(app: AppHandle, mods: Vec<u64>) -> Result<DownloadReport, String> {
+    let conf: Config = read_config()?;
+
+    let (job, cancel) = DOWNLOAD_JOBS.create(mods.clone());
+    let job_id = job.id;
+    emit_job(&app, Some(job));
+    info!("Starting download job {} for mods: {:?}", job_id, mods);
+
+    let result = run_download_job(&app, job_id, conf, mods, &cancel).await;
+    emit_job(
+        &app,
+        DOWNLOAD_JOBS.finish(job_id, result.as_ref().err().cloned()),
+    );
+
+    let report = result?;
+    for failed in report.failed() {
+        warn!(
+            "Mod {} failed to download: {}",
+            failed.item_id,
+            failed.reason.as_deref().unwrap_or("unknown reason")
+        );
+    }
+    Ok(report)
+}
+
+/// Applies the SteamCMD home, credentials and retry policy from `conf`.
+///
+/// Returns the configured number of parallel SteamCMD processes.
+fn configure_steamcmd(steamcmd: &mut SteamCMD, conf: Config) -> Result<usize, String> {
+    let steamcmd_home = PathBuf::from_str(conf.steamcmd_home.as_str())
+        .map_err(|e| format!("{}, failed to set steamcmd home.", e))?;
+    let steamcmd_config = conf.steamcmd_config.unwrap_or_default();
+    let credentials = Credentials::from_config(
+        steamcmd_config.username.as_deref(),
+        steamcmd_config.password.as_deref(),
+    );
+    match &credentials {
+        Some(c) if c.is_cached() => info!("Logging into SteamCMD as {} (cached)", c.username),
+        Some(c) => info!("Logging into SteamCMD as {}", c.username),
+        None => info!("Logging into SteamCMD anonymously"),
+    }
+    let retry = steamcmd_config
+        .retry
+        .map(|r| RetryPolicy::new(r.max_retries as u32, Duration::from_secs(r.backoff_secs)))
+        .unwrap_or_default();
+
+    steamcmd
+        .set_steamcmd_home(steamcmd_home)
+        .set_credentials(credentials)
+        .set_retry_policy(retry);
+    Ok(steamcmd_config.parallel as usize)
+}
+
+/// Waits for exclusive access to SteamCMD on behalf of a job, giving up if it is cancelled.
+async fn acquire_steamcmd(
+    app: &AppHandle,
+    job_id: u64,
+    cancel: &CancellationToken,
+) -> Result<RwLockWriteGuard<'static, SteamCMD>, String> {
+    // Only one job drives SteamCMD at a time, the others stay queued here.
+    let steamcmd = tokio::select! {
+        steamcmd = STEAMCMD_MANAGER.write() => steamcmd,
+        _ = cancel.cancelled() => {
+            return Err(format!("Download job {} cancelled while queued.", job_id));
+        }
+    };
+    emit_job(app, DOWNLOAD_JOBS.start(job_id));
+    Ok(steamcmd)
+}
+
+/// Forwards a job's SteamCMD events: PIDs go to the registry, item progress to the frontend.
+fn forward_events(
+    app: &AppHandle,
+    job_id: u64,
+    mut rx: UnboundedReceiver<SteamCmdEvent>,
+) -> JoinHandle<()> {
+    let app = app.clone();
+    tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            if let SteamCmdEvent::ProcessStarted { pid } = event {
+                DOWNLOAD_JOBS.add_pid(job_id, pid);
+                continue;
+            }
+            if event.item_id().is_none() {
+                debug!("steamcmd: {:?}", event);
+                continue;
+            }
+            if let Err(e) = app.emit(DOWNLOAD_PROGRESS_EVENT, &event) {
+                warn!("Failed to emit download progress: {}", e);
+            }
+        }
+    })
+}
+
+async fn run_download_job(
+    app: &AppHandle,
+    job_id: u64,
+    conf: Config,
+    mods: Vec<u64>,
+    cancel: &CancellationToken,
+) -> Result<DownloadReport, String> {
+    let mut steamcmd = acquire_steamcmd(app, job_id, cancel).await?;
+    let parallel = configure_steamcmd(&mut steamcmd, conf)?;
+
+    let (tx, rx) = unbounded_channel::<SteamCmdEvent>();
+    let forwarder = forward_events(app, job_id, rx);
+    let result = steamcmd
+        .download_mod_par(BAROTRAUMA_GAME_ID, mods, parallel, Some(&tx), Some(cancel))
+        .await
+        .map_err(|e| format!("{e}, failed to download mods."));
+
+    drop(tx);
+    forwarder.await.ok();
+    result
+}
+
+/// Verifies installed mods against the hash cache and repairs the damaged ones.
+///
+/// Each item is validated by SteamCMD as a job in [`DOWNLOAD_JOBS`]. With the Copy
+/// install strategy the copy in `LocalMods` is checked as well and copied again from
+/// the validated workshop folder when it no longer matches.
 #[tauri::command]
-pub async fn download_mods(mods: Vec<u64>) -> Result<(), String> {
+pub async fn verify_mods(app: AppHandle, mod_ids: Vec<u64>) -> Result<VerifyReport, String> {
     let conf: Config = read_config()?;
+    let is_copy = conf.install_strategy == InstallStrategy::Copy as i32;
+    let expected: HashMap<u64, String> = load_hash_cache()
+        .into_iter()
+        .filter(|(id, _)| mod_ids.contains(id))
+        .collect();
+
+    let (job, cancel) = DOWNLOAD_JOBS.create(mod_ids.clone());
+    let job_id = job.id;
+    emit_job(&app, Some(job));
+    info!("Starting verify job {} for mods: {:?}", job_id, mod_ids);
+
+    let result = async {
+        let mut steamcmd = acquire_steamcmd(&app, job_id, &cancel).await?;
+        let parallel = configure_steamcmd(&mut steamcmd, conf)?;
+
+        let (tx, rx) = unbounded_channel::<SteamCmdEvent>();
+        let forwarder = forward_events(&app, job_id, rx);
+        let result = steamcmd
+            .verify_items(
+                BAROTRAUMA_GAME_ID,
+                mod_ids,
+                &expected,
+                parallel,
+                Some(&tx),
+                Some(&cancel),
+            )
+            .await
+            .map_err(|e| format!("{e}, failed to verify mods."));
+        drop(tx);
+        forwarder.await.ok();
+
+        let mut report = result?;
+        if is_copy {
+            repair_installed_copies(&steamcmd, &mut report).await;
+        }
+        Ok(report)
+    }
+    .await;
+    emit_job(
+        &app,
+        DOWNLOAD_JOBS.finish(job_id, result.as_ref().err().cloned()),
+    );
+
+    let report = result?;
+    refresh_hash_cache(&report);
+    info!(
+        "Verify job {} done: corrupted {:?}, repaired {:?}",
+        job_id,
+        report.corrupted(),
+        report.repaired()
+    );
+    Ok(report)
+}
 
-    info!("Starting to download mods: {:?}", mods);
+/// Records the expected hashes of verified items that validation moved to a newer
+/// Workshop version, so the next run compares against the update.
+fn refresh_hash_cache(report: &VerifyReport) {
+    let mut cache = load_hash_cache();
+    let mut changed = false;
+    for item in &report.items {
+        if let (Integrity::Intact | Integrity::Repaired, Some(hash)) =
+            (item.status, &item.expected_hash)
+        {
+            changed |= cache.insert(item.item_id, hash.clone()).as_ref() != Some(hash);
+        }
+    }
+    if changed && let Err(e) = save_hash_cache(&cache) {
+        warn!("{}", e);
+    }
+}
+
+/// Copies validated workshop items over installed copies that no longer match their hash.
+async fn repair_installed_copies(steamcmd: &SteamCMD, report: &mut VerifyReport) {
+    let manager = BARO_MANAGER.read().await;
+    for item in report.items.iter_mut() {
+        let Some(expected) = item.expected_hash.clone() else {
+            continue;
+        };
+        if matches!(item.status, Integrity::Corrupted)
+            || manager.get_mod_hash(item.item_id).ok().as_ref() == Some(&expected)
+        {
+            continue;
+        }
+
+        let repaired = steamcmd
+            .workshop_item_dir(BAROTRAUMA_GAME_ID, item.item_id)
+            .map_err(|e| e.to_string())
+            .and_then(|src| {
+                let dest = manager.mod_dir()?.join(item.item_id.to_string());
+                if dest.exists() {
+                    fs::remove_dir_all(&dest).map_err(|e| e.to_string())?;
+                }
+                copy_dir_recursive(&src, &dest).map_err(|e| e.to_string())?;
+                fs_utils::hash_directory(&dest).map_err(|e| e.to_string())
+            });
+        match repaired {
+            Ok(hash) if hash == expected => {
+                item.status = Integrity::Repaired;
+                item.reason = None;
+            }
+            Ok(_) => {
+                item.status = Integrity::Corrupted;
+                item.reason = Some("Installed copy differs after repair".to_string());
+            }
+            Err(e) => {
+                item.status = Integrity::Corrupted;
+                item.reason = Some(format!("{e}, failed to repair installed copy."));
+            }
+        }
+    }
+}
+
+/// Installed mods by workshop ID, mapped to the folder each one lives in.
+async fn installed_mod_dirs() -> Result<HashMap<u64, PathBuf>, String> {
+    Ok(list_installed_mods()
+        .await?
+        .into_iter()
+        .filter_map(|m| Some((m.steam_workshop_id, PathBuf::from(m.home_dir?))))
+        .collect())
+}
+
+/// Lists SteamCMD's workshop cache with sizes, marking which entries are copied into
+/// `LocalMods`, symlinked from it, or orphaned.
+#[tauri::command]
+pub async fn list_workshop_cache() -> Result<Vec<CacheEntry>, String> {
+    let conf: Config = read_config()?;
+    let installed = installed_mod_dirs().await?;
     STEAMCMD_MANAGER
         .write()
         .await
-        .set_steamcmd_home(
-            PathBuf::from_str(conf.steamcmd_home.as_str())
-                .map_err(|e| format!("{}, failed to set steamcmd home.", e))?,
-        )
-        .download_mod_par(
-            BAROTRAUMA_GAME_ID,
-            mods,
-            conf.steamcmd_config.unwrap().parallel as usize,
-        )
+        .set_steamcmd_home(PathBuf::from(conf.steamcmd_home))
+        .cache_entries(BAROTRAUMA_GAME_ID, &installed)
+        .map_err(|e| format!("{e}, failed to list workshop cache."))
+}
+
+/// Removes orphaned and/or already copied entries from the workshop cache.
+///
+/// With `dry_run` set nothing is deleted, the report only tells what would be reclaimed.
+#[tauri::command]
+pub async fn prune_workshop_cache(options: PruneOptions) -> Result<PruneReport, String> {
+    let conf: Config = read_config()?;
+    let installed = installed_mod_dirs().await?;
+    // Holding the write lock keeps downloads from writing into the cache meanwhile.
+    let report = STEAMCMD_MANAGER
+        .write()
         .await
+        .set_steamcmd_home(PathBuf::from(conf.steamcmd_home))
+        .prune_cache(BAROTRAUMA_GAME_ID, &installed, options)
+        .map_err(|e| format!("{e}, failed to prune workshop cache."))?;
+
+    info!(
+        "{} {} workshop cache entries, {} bytes",
+        if report.dry_run {
+            "Would prune"
+        } else {
+            "Pruned"
+        },
+        report.removed.len(),
+        report.bytes_reclaimed
+    );
+    for (item_id, e) in &report.errors {
+        warn!("Failed to remove workshop cache entry {}: {}", item_id, e);
+    }
+    Ok(report)
 }
+
+/// Cancels a queued or running download job.
+///
+/// Kills the job's SteamCMD processes and removes partially downloaded items
+/// before returning the job's final state.
+#[tauri::command]
+pub async fn cancel_download(job_id: u64) -> Result<JobInfo, String> {
+    info!("Cancelling download job {}", job_id);
+    DOWNLOAD_JOBS.cancel(job_id, CANCEL_TIMEOUT).await
+}
+
+/// Installs SteamCMD into the application's own directory and points the config at it.
+///
+/// `url` overrides where the SteamCMD archive is fetched from, which defaults to
+/// Valve's CDN. Returns the new SteamCMD home.
+#[tauri::command]
+pub async fn install_steamcmd(url: Option<String>) -> Result<String, String> {
+    let home = MANAGED_STEAMCMD_HOME.clone();
+    let mut installer = Installer::new(home.clone());
+    if let Some(url) = url {
+        installer = installer.with_url(url);
+    }
+
+    info!("Installing SteamCMD into {:?}", home);
+    let (tx, mut rx) = unbounded_channel::<SteamCmdEvent>();
+    let logger = tokio::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            debug!("steamcmd: {:?}", event);
+        }
+    });
+    let result = installer
+        .install(Some(&tx))
+        .await
+        .map_err(|e| format!("{e}, failed to install steamcmd."));
+    drop(tx);
+    logger.await.ok();
+    result?;
+
+    let home = home.to_string_lossy().to_string();
+    let mut conf = read_config()?;
+    conf.steamcmd_home = home.clone();
+    write_config(conf)?;
+    info!("SteamCMD installed into {}", home);
+    Ok(home)
+}
+
+/// Lists queued, running and recently finished download jobs, oldest first.
+#[tauri::command]
+pub fn list_jobs() -> Vec<JobInfo> {
+    DOWNLOAD_JOBS.list()
+}
+
 #[tauri::command]
 pub async fn list_mod_lists() -> Result<Vec<ModList>, String> {
     let conf: Config = read_config()?;
@@ -351,6 +809,45 @@ pub async fn get_background_image() -> Result<Option<String>, String> {
     }
 }
 
+/// Returns a thumbnail of a workshop preview image as a data URL.
+///
+/// `hash` is the mod's `previewHash`. The preview is downloaded from `url` only once,
+/// afterwards the cached thumbnail closest to `size` pixels is served, also offline.
+#[tauri::command]
+pub async fn get_preview_thumbnail(hash: String, url: String, size: u32) -> Result<String, String> {
+    if let Some(path) = PREVIEW_CACHE.get(&hash, size) {
+        return thumbnail_data_url(&path)
+            .map_err(|e| format!("{e}, failed to read preview thumbnail."));
+    }
+
+    let image = STEAM_WORKSHOP_CLIENT
+        .read()
+        .await
+        .download_preview(&url)
+        .map_err(|e| format!("{e}, failed to download preview image."))
+        .await?;
+    // Decoding and encoding the thumbnails is CPU bound
+    tauri::async_runtime::spawn_blocking(move || -> Result<String, String> {
+        PREVIEW_CACHE
+            .insert(&hash, &image)
+            .map_err(|e| format!("{e}, failed to cache preview image."))?;
+        let path = PREVIEW_CACHE
+            .get(&hash, size)
+            .ok_or("Preview thumbnail missing after caching.")?;
+        thumbnail_data_url(&path).map_err(|e| format!("{e}, failed to read preview thumbnail."))
+    })
+    .await
+    .map_err(|e| format!("{e}, failed to cache preview image."))?
+}
+
+/// Removes every cached preview thumbnail.
+#[tauri::command]
+pub fn clear_preview_cache() -> Result<(), String> {
+    PREVIEW_CACHE
+        .clear()
+        .map_err(|e| format!("{e}, failed to clear the preview cache."))
+}
+
 /// Returns the version information of the application.
 #[tauri::command]
 pub fn get_build_info() -> BuildInfo {
@@ -374,10 +871,54 @@ pub async fn is_barotrauma_mod(item_id: u64) -> Result<bool, String> {
         .map(|item: WorkshopItem| item.consumer_app_id == BAROTRAUMA_GAME_ID)
 }
 
+/// Checks many workshop items at once before they are downloaded.
+///
+/// Each result lists why the item should not be downloaded: missing, not a Barotrauma
+/// mod, banned, or hidden from anonymous downloads.
+#[tauri::command]
+pub async fn validate_workshop_items(item_ids: Vec<u64>) -> Result<Vec<ItemValidation>, String> {
+    let conf: Config = read_config()?;
+    STEAM_WORKSHOP_CLIENT
+        .read()
+        .await
+        .validate_items(
+            item_ids,
+            BAROTRAUMA_GAME_ID,
+            conf.metadata_retrieve_batchsize as usize,
+        )
+        .map_err(|e| format!("{e}, failed to validate workshop items."))
+        .await
+}
+
+/// Installs downloaded mods into `LocalMods` using the configured install strategy.
+///
+/// Only items whose workshop folder actually exists are installed; the rest are
+/// reported back as an error after the available ones have been installed.
 #[tauri::command]
 pub async fn install_mods(mod_ids: Vec<u64>) -> Result<(), String> {
     let conf: Config = read_config()?;
 
+    if conf.steamcmd_home.is_empty() {
+        return Err("SteamCMD home is not set, failed to install mods.".to_string());
+    }
+
+    let (mod_ids, missing): (Vec<u64>, Vec<u64>) = {
+        let mut steamcmd = STEAMCMD_MANAGER.write().await;
+        steamcmd.set_steamcmd_home(PathBuf::from(&conf.steamcmd_home));
+        mod_ids
+            .into_iter()
+            .partition(|&id| steamcmd.is_item_downloaded(BAROTRAUMA_GAME_ID, id))
+    };
+    if !missing.is_empty() {
+        warn!("Skipping mods that have not been downloaded: {:?}", missing);
+    }
+    if mod_ids.is_empty() {
+        return Err(format!(
+            "None of the requested mods have been downloaded: {:?}",
+            missing
+        ));
+    }
+
     let fut: Vec<_> = mod_ids
         .iter()
         .copied()
@@ -387,7 +928,8 @@ pub async fn install_mods(mod_ids: Vec<u64>) -> Result<(), String> {
                     let mod_dir: PathBuf = STEAMCMD_MANAGER
                         .read()
                         .await
-                        .workshop_item_dir(BAROTRAUMA_GAME_ID, item_id)?;
+                        .workshop_item_dir(BAROTRAUMA_GAME_ID, item_id)
+                        .map_err(|e| e.to_string())?;
                     let dest = BARO_MANAGER.read().await.mod_dir()?.join(
                         mod_dir
                             .file_name()
@@ -405,7 +947,8 @@ pub async fn install_mods(mod_ids: Vec<u64>) -> Result<(), String> {
                         STEAMCMD_MANAGER
                             .read()
                             .await
-                            .workshop_item_dir(BAROTRAUMA_GAME_ID, item_id)?,
+                            .workshop_item_dir(BAROTRAUMA_GAME_ID, item_id)
+                            .map_err(|e| e.to_string())?,
                         BARO_MANAGER
                             .read()
                             .await
@@ -437,7 +980,16 @@ pub async fn install_mods(mod_ids: Vec<u64>) -> Result<(), String> {
     }
     save_hash_cache(&cache)?;
 
-    Ok(())
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Installed {} mod(s), but {} were not downloaded: {:?}",
+            mod_ids.len(),
+            missing.len(),
+            missing
+        ))
+    }
 }
 #[tauri::command]
 pub async fn uninstall_mods(mod_ids: Vec<u64>) -> Result<(), String> {
@@ -541,6 +1093,31 @@ pub async fn get_mod_hash(mod_id: u64) -> Result<String, String> {
     BARO_MANAGER.read().await.get_mod_hash(mod_id)
 }
 
+/// Compares every installed mod with the `expectedhash` of its filelist.xml.
+///
+/// A mismatch means the mod was edited locally or only partly copied, and players
+/// using it get kicked from multiplayer sessions.
+#[tauri::command]
+pub async fn verify_expected_hashes() -> Result<Vec<HashCheck>, String> {
+    // Hashing every content file is IO and CPU bound
+    tauri::async_runtime::spawn_blocking(|| BARO_MANAGER.blocking_read().verify_expected_hashes())
+        .await
+        .map_err(|e| format!("{e}, failed to verify expected hashes."))?
+}
+
+/// Checks the files an installed mod references for missing files, case mismatched
+/// paths and malformed XML, and records the mod's health score.
+#[tauri::command]
+pub async fn validate_mod(mod_id: u64) -> Result<ModValidation, String> {
+    BARO_MANAGER.write().await.validate_mod(mod_id)
+}
+
+/// Lists the files an installed mod's filelist.xml references, with resolved paths.
+#[tauri::command]
+pub async fn get_mod_content_files(mod_id: u64) -> Result<Vec<ContentFile>, String> {
+    BARO_MANAGER.read().await.get_mod_content_files(mod_id)
+}
+
 #[tauri::command]
 pub async fn check_mod_updates(mod_ids: Vec<u64>) -> Result<Vec<ModUpdateStatus>, String> {
     let cache = load_hash_cache();
@@ -592,6 +1169,26 @@ pub async fn get_workshop_items(item_ids: Vec<u64>) -> Result<Vec<WorkshopItem>,
         .await
 }
 
+/// The core package currently selected in the player config, "Vanilla" if there is none.
+fn current_base_package() -> Result<String, String> {
+    let conf: Config = read_config()?;
+    let game_home = constants::BarotraumaHome::new(
+        PathBuf::from_str(&conf.game_home).map_err(|e| format!("{e}, invalid game home path."))?,
+    );
+    let config_file = game_home.player_config_file();
+    if !config_file.exists() {
+        return Ok("Vanilla".to_string());
+    }
+    let baro_conf = mod_analyzer::BaroConfig::from_file(&config_file)
+        .map_err(|e| format!("{e}, failed to read player config."))?;
+    Ok(baro_conf
+        .core_package()
+        .rsplit('/')
+        .next()
+        .unwrap_or("Vanilla")
+        .replace(".xml", ""))
+}
+
 #[tauri::command]
 pub async fn create_mod_list(profile_name: String) -> Result<ModList, String> {
     let enabled = list_enabled_mods().await?;
@@ -599,30 +1196,9 @@ pub async fn create_mod_list(profile_name: String) -> Result<ModList, String> {
         return Err("No enabled mods to save as a profile.".to_string());
     }
 
-    // Read config to get the base package
-    let conf: Config = read_config()?;
-    let game_home = constants::BarotraumaHome::new(
-        PathBuf::from_str(&conf.game_home).map_err(|e| format!("{e}, invalid game home path."))?,
-    );
-    let base_package = {
-        let config_file = game_home.player_config_file();
-        if config_file.exists() {
-            let baro_conf = mod_analyzer::BaroConfig::from_file(&config_file)
-                .map_err(|e| format!("{e}, failed to read player config."))?;
-            baro_conf
-                .core_package()
-                .rsplit('/')
-                .next()
-                .unwrap_or("Vanilla")
-                .replace(".xml", "")
-        } else {
-            "Vanilla".to_string()
-        }
-    };
-
     let mod_list = ModList {
         profile_name: profile_name.clone(),
-        base_package,
+        base_package: current_base_package()?,
         mods: enabled.iter().map(|m| m.name.clone()).collect(),
     };
 
@@ -744,6 +1320,104 @@ pub async fn export_profile(profile_name: String, export_path: String) -> Result
     Ok(())
 }
 
+/// Turns a Steam Workshop collection into a mod profile.
+///
+/// `collection` is a collection ID or its workshop URL. Nested collections are expanded,
+/// children that are not installed yet are downloaded and installed, and the profile lists
+/// the mods in the collection's order. It is named after the collection unless
+/// `profile_name` is given.
+#[tauri::command]
+pub async fn import_collection(
+    app: AppHandle,
+    collection: String,
+    profile_name: Option<String>,
+) -> Result<ModList, String> {
+    let collection_id = steam_api::parse_item_id(&collection)
+        .ok_or_else(|| format!("'{}' is not a workshop collection ID or URL.", collection))?;
+    let (children, title) = {
+        let client = STEAM_WORKSHOP_CLIENT.read().await;
+        let children = client
+            .get_collection_items(collection_id)
+            .map_err(|e| format!("{e}, failed to read collection {}.", collection_id))
+            .await?;
+        let title = client
+            .get_item(collection_id)
+            .await
+            .ok()
+            .map(|item| item.title);
+        (children, title)
+    };
+    if children.is_empty() {
+        return Err(format!(
+            "Workshop item {} is not a collection or is empty.",
+            collection_id
+        ));
+    }
+    info!(
+        "Collection {} lists {} mods: {:?}",
+        collection_id,
+        children.len(),
+        children
+    );
+
+    let installed: HashSet<u64> = list_installed_mods()
+        .await?
+        .iter()
+        .map(|m| m.steam_workshop_id)
+        .collect();
+    let missing: Vec<u64> = children
+        .iter()
+        .copied()
+        .filter(|id| !installed.contains(id))
+        .collect();
+    if !missing.is_empty() {
+        let report = download_mods(app, missing).await?;
+        let succeeded = report.succeeded();
+        if !succeeded.is_empty() {
+            install_mods(succeeded).await?;
+        }
+    }
+
+    let names: HashMap<u64, String> = BARO_MANAGER
+        .write()
+        .await
+        .refresh_mods()?
+        .get_mods()
+        .iter()
+        .map(|m| (m.steam_workshop_id, m.name.clone()))
+        .collect();
+    let (mods, unavailable): (Vec<_>, Vec<_>) = children
+        .iter()
+        .map(|id| (*id, names.get(id).cloned()))
+        .partition(|(_, name)| name.is_some());
+    if !unavailable.is_empty() {
+        warn!(
+            "Collection mods left out of the profile, they could not be installed: {:?}",
+            unavailable.iter().map(|(id, _)| id).collect::<Vec<_>>()
+        );
+    }
+
+    let mod_list = ModList {
+        profile_name: profile_name
+            .filter(|name| !name.trim().is_empty())
+            .or(title.filter(|title| !title.trim().is_empty()))
+            .unwrap_or_else(|| format!("Collection {}", collection_id))
+            // The profile is saved as `<name>.xml`, workshop titles may contain anything.
+            .replace(['/', '\\', ':', '*', '?', '"', '<', '>', '|'], "_"),
+        base_package: current_base_package()?,
+        mods: mods.into_iter().filter_map(|(_, name)| name).collect(),
+    };
+    BARO_MANAGER.read().await.save_mod_list(&mod_list)?;
+
+    info!(
+        "Imported collection {} as profile '{}' with {} mods",
+        collection_id,
+        mod_list.profile_name,
+        mod_list.mods.len()
+    );
+    Ok(mod_list)
+}
+
 /// Imports a profile from an XML file at the given path.
 #[tauri::command]
 pub async fn import_profile(path: String) -> Result<ModList, String> {
@@ -805,8 +1479,21 @@ fn backup_player_config() -> Result<(), String> {
     Ok(())
 }
 
+/// Result of `apply_mod_list`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedProfile {
+    /// The installed game's version, when it could be detected.
+    pub game_version: Option<String>,
+    /// Enabled mods built for an older or newer game version than the installed one.
+    pub incompatible_mods: Vec<BarotraumaMod>,
+}
+
+/// Enables the mods of a saved profile in its order and makes it the active profile.
+///
+/// Warns about enabled mods that were built for another game version.
 #[tauri::command]
-pub async fn apply_mod_list(profile_name: String) -> Result<(), String> {
+pub async fn apply_mod_list(profile_name: String) -> Result<AppliedProfile, String> {
     // 1. Read the profile
     let mod_list_dir = BARO_MANAGER.read().await.mod_list_dir()?.clone();
     let profile_path = mod_list_dir.join(format!("{}.xml", profile_name));
@@ -841,7 +1528,27 @@ pub async fn apply_mod_list(profile_name: String) -> Result<(), String> {
     write_regularpackages(&resolved_ids)?;
 
     // 5. Refresh mod manager state
-    BARO_MANAGER.write().await.refresh_mods()?;
+    let mut manager = BARO_MANAGER.write().await;
+    manager.refresh_mods()?;
+    let game_version = manager.game_version().ok();
+    let incompatible_mods: Vec<BarotraumaMod> = manager
+        .enabled_mods()?
+        .into_iter()
+        .filter(|m| {
+            m.game_compatibility.is_some()
+                && m.game_compatibility() != GameCompatibility::Compatible
+        })
+        .collect();
+    drop(manager);
+    for m in &incompatible_mods {
+        warn!(
+            "Mod '{}' was built for game version {} ({}), installed is {}.",
+            m.name,
+            m.game_version,
+            m.game_compatibility().as_str_name(),
+            game_version.map(|v| v.to_string()).unwrap_or_default()
+        );
+    }
 
     // 6. Record the applied profile as active
     let mut conf = read_config()?;
@@ -854,7 +1561,10 @@ pub async fn apply_mod_list(profile_name: String) -> Result<(), String> {
         resolved_ids.len(),
         mod_list.mods.len()
     );
-    Ok(())
+    Ok(AppliedProfile {
+        game_version: game_version.map(|v| v.to_string()),
+        incompatible_mods,
+    })
 }
 
 #[tauri::command]
@@ -899,16 +1609,48 @@ pub async fn reorder_enabled_mods(ordered_ids: Vec<u64>) -> Result<(), String> {
 /// Detects missing dependencies among currently enabled mods.
 ///
 /// Reads each enabled mod's content.xml, extracts `<package>` dependency
-/// declarations, and reports any dependency that is not found among the
-/// set of enabled mods (matched by either Steam Workshop ID or name).
+/// declarations, merges them with the mod's Steam Workshop "Required Items", and
+/// reports any dependency that is not found among the set of enabled mods
+/// (matched by either Steam Workshop ID or name).
+///
+/// When the Workshop cannot be reached only the content.xml declarations are checked.
+/// Also reports item, character and other prefab identifiers defined by several
+/// enabled mods, with the definition that wins under the current load order.
 #[tauri::command]
 pub async fn detect_mod_conflicts() -> Result<ConflictReport, String> {
     let manager = BARO_MANAGER.read().await;
     let enabled = manager
         .enabled_mods()
         .map_err(|e| format!("{e}, failed to get enabled mods."))?;
+    let content_files = manager
+        .enabled_mod_content_files()
+        .map_err(|e| format!("{e}, failed to get content files of enabled mods."))?;
+    let installed_names: HashMap<u64, String> = manager
+        .get_mods()
+        .iter()
+        .map(|m| (m.steam_workshop_id, m.name.clone()))
+        .collect();
     drop(manager);
 
+    let conf: Config = read_config()?;
+    let workshop_ids: Vec<u64> = enabled
+        .iter()
+        .map(|m| m.steam_workshop_id)
+        .filter(|&id| id > 0)
+        .collect();
+    let client = STEAM_WORKSHOP_CLIENT.read().await;
+    let required_items = if workshop_ids.is_empty() {
+        HashMap::new()
+    } else {
+        client
+            .get_required_items(workshop_ids, conf.metadata_retrieve_batchsize as usize)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("{e}, failed to get workshop required items, checking content.xml only.");
+                HashMap::new()
+            })
+    };
+
     // Build lookup sets from enabled mods
     let enabled_ids: HashSet<u64> = enabled
         .iter()
@@ -925,14 +1667,15 @@ pub async fn detect_mod_conflicts() -> Result<ConflictReport, String> {
             None => continue,
         };
         let xml_path = home_dir.join(constants::MOD_FILELIST_FILE);
-        let xml = match std::fs::read_to_string(&xml_path) {
-            Ok(x) => x,
-            Err(_) => continue,
-        };
-        let deps = match parse_dependencies(&xml) {
-            Ok(d) => d,
-            Err(_) => continue,
-        };
+        let declared = std::fs::read_to_string(&xml_path)
+            .ok()
+            .and_then(|xml| parse_dependencies(&xml).ok())
+            .unwrap_or_default();
+        let required = required_items
+            .get(&mod_obj.steam_workshop_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let deps = merge_dependencies(declared, required);
 
         for dep in &deps {
             let found = match dep.steam_workshop_id {
@@ -945,13 +1688,56 @@ pub async fn detect_mod_conflicts() -> Result<ConflictReport, String> {
                     mod_steam_id: mod_obj.steam_workshop_id,
                     dependency_name: dep.name.clone(),
                     dependency_steam_id: dep.steam_workshop_id,
+                    source: dep.source,
                 });
             }
         }
     }
 
+    // Dependencies only known from the Workshop are named by their ID so far
+    let unnamed: Vec<u64> = missing
+        .iter()
+        .filter(|dep| dep.source == DependencySource::Workshop)
+        .filter_map(|dep| dep.dependency_steam_id)
+        .filter(|id| !installed_names.contains_key(id))
+        .collect();
+    let titles: HashMap<u64, String> = if unnamed.is_empty() {
+        HashMap::new()
+    } else {
+        match client
+            .get_items_cached(unnamed, conf.metadata_retrieve_batchsize as usize)
+            .await
+        {
+            Ok(items) => items
+                .into_iter()
+                .map(|cached| (cached.value.published_file_id, cached.value.title))
+                .collect(),
+            Err(e) => {
+                warn!("{e}, failed to get titles of workshop required items.");
+                HashMap::new()
+            }
+        }
+    };
+    for dep in missing
+        .iter_mut()
+        .filter(|dep| dep.source == DependencySource::Workshop)
+    {
+        if let Some(name) = dep
+            .dependency_steam_id
+            .and_then(|id| installed_names.get(&id).or_else(|| titles.get(&id)))
+        {
+            dep.dependency_name = name.clone();
+        }
+    }
+
+    let content_conflicts =
+        tauri::async_runtime::spawn_blocking(move || detect_content_conflicts(&content_files))
+            .await
+            .map_err(|e| format!("{e}, failed to detect content conflicts."))?;
+
     Ok(ConflictReport {
         missing_dependencies: missing,
+        content_conflicts,
     })
 }
 
@@ -964,9 +1750,14 @@ pub struct WorkshopUpdateStatus {
     pub has_update: bool,
     pub local_last_modified: Option<u64>,
     pub workshop_last_updated: Option<u64>,
+    /// Whether the workshop side came from Steam or from the metadata cache.
+    pub workshop_source: Option<Source>,
 }
 
 /// Checks installed mods against Steam Workshop for available updates.
+///
+/// Workshop timestamps come from the metadata cache while they are fresh, so the
+/// result may lag behind Steam by up to the configured TTL.
 #[tauri::command]
 pub async fn check_workshop_updates() -> Result<Vec<WorkshopUpdateStatus>, String> {
     let enabled = BARO_MANAGER.read().await.enabled_mods()?;
@@ -984,16 +1775,32 @@ pub async fn check_workshop_updates() -> Result<Vec<WorkshopUpdateStatus>, Strin
     }
 
     let conf: Config = read_config()?;
-    let workshop_items = STEAM_WORKSHOP_CLIENT
+    let workshop_items = match STEAM_WORKSHOP_CLIENT
         .read()
         .await
-        .get_items_batched(ids.clone(), conf.metadata_retrieve_batchsize as usize)
-        .map_err(|e| format!("{e}, failed to check workshop updates."))
-        .await?;
+        .get_items_cached(ids.clone(), conf.metadata_retrieve_batchsize as usize)
+        .await
+    {
+        Ok(items) => items,
+        // Nothing cached to fall back on, report the mods without workshop times
+        Err(steam_api::Error::RateLimited { retry_after }) => {
+            warn!(
+                "Rate limited by Steam (retry after {:?}), workshop update check skipped.",
+                retry_after
+            );
+            Vec::new()
+        }
+        Err(e) => return Err(format!("{e}, failed to check workshop updates.")),
+    };
 
-    let workshop_map: HashMap<u64, &steam_api::WorkshopItem> = workshop_items
+    let workshop_map: HashMap<u64, (&WorkshopItem, Source)> = workshop_items
         .iter()
-        .map(|item| (item.published_file_id, item))
+        .map(|cached| {
+            (
+                cached.value.published_file_id,
+                (&cached.value, cached.source),
+            )
+        })
         .collect();
 
     let results: Vec<WorkshopUpdateStatus> = enabled
@@ -1001,7 +1808,7 @@ pub async fn check_workshop_updates() -> Result<Vec<WorkshopUpdateStatus>, Strin
         .filter(|m| m.steam_workshop_id > 0)
         .map(|m| {
             let workshop = workshop_map.get(&m.steam_workshop_id);
-            let workshop_time = workshop.map(|w| w.time_updated);
+            let workshop_time = workshop.map(|(w, _)| w.time_updated);
             let local_time = m.last_modified;
             let has_update = match (local_time, workshop_time) {
                 (Some(local), Some(workshop)) => workshop > local,
@@ -1014,6 +1821,7 @@ pub async fn check_workshop_updates() -> Result<Vec<WorkshopUpdateStatus>, Strin
                 has_update,
                 local_last_modified: local_time,
                 workshop_last_updated: workshop_time,
+                workshop_source: workshop.map(|(_, source)| *source),
             }
         })
         .collect();
@@ -1021,23 +1829,45 @@ pub async fn check_workshop_updates() -> Result<Vec<WorkshopUpdateStatus>, Strin
     Ok(results)
 }
 
+/// Fetches the workshop change notes of a mod, newest first.
+///
+/// For an installed mod only the notes published after it was installed are returned,
+/// which are the changes an update would bring.
+#[tauri::command]
+pub async fn get_mod_change_notes(mod_id: u64) -> Result<Vec<ChangeNote>, String> {
+    let installed = BARO_MANAGER
+        .read()
+        .await
+        .get_mods()
+        .iter()
+        .find(|m| m.steam_workshop_id == mod_id)
+        .cloned();
+    let client = STEAM_WORKSHOP_CLIENT.read().await.clone();
+    retrieve_change_notes(mod_id, installed.as_ref(), &client)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Network connectivity status.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NetworkStatus {
     pub steam_api: bool,
+    /// Steam answered but kept rate limiting the probe request.
+    pub steam_api_rate_limited: bool,
     pub steamcmd_available: bool,
 }
 
 /// Checks network connectivity to Steam services.
 #[tauri::command]
 pub async fn check_network_status() -> NetworkStatus {
-    let steam_api_ok = STEAM_WORKSHOP_CLIENT
+    let probe = STEAM_WORKSHOP_CLIENT
         .read()
         .await
         .get_items_batched(vec![602960], 1) // Barotrauma itself as a test
-        .await
-        .is_ok();
+        .await;
+    let steam_api_rate_limited = matches!(probe, Err(steam_api::Error::RateLimited { .. }));
+    let steam_api_ok = probe.is_ok() || steam_api_rate_limited;
 
     let conf = read_config().ok();
     let steamcmd_path = conf.as_ref().and_then(|c| {
@@ -1048,31 +1878,28 @@ pub async fn check_network_status() -> NetworkStatus {
         }
     });
     let steamcmd_available = steamcmd_path
-        .map(|p| {
-            Path::new(p).join("steamcmd.exe").exists() || Path::new(p).join("steamcmd").exists()
-        })
+        .map(|p| SteamCMD::new(PathBuf::from(p)).steamcmd_full_path().is_ok())
         .unwrap_or(false);
 
     NetworkStatus {
         steam_api: steam_api_ok,
+        steam_api_rate_limited,
         steamcmd_available,
     }
 }
 
-/// Popular Barotrauma workshop mod IDs for browsing.
-const POPULAR_MOD_IDS: &[u64] = &[
-    2942414988, 2942414988, // Placeholder — will be replaced with real IDs
-];
-
-/// Returns popular Barotrauma mods from the Steam Workshop.
+/// Browses or searches Barotrauma mods on the Steam Workshop, one page at a time.
+///
+/// Pass the returned `nextCursor` back in `query.cursor` to fetch the following page.
 #[tauri::command]
-pub async fn get_popular_mods() -> Result<Vec<WorkshopItem>, String> {
+pub async fn browse_workshop(mut query: WorkshopQuery) -> Result<QueryPage, String> {
     let conf: Config = read_config()?;
-    let ids: Vec<u64> = POPULAR_MOD_IDS.to_vec();
-    STEAM_WORKSHOP_CLIENT
-        .read()
-        .await
-        .get_items_batched(ids, conf.metadata_retrieve_batchsize as usize)
-        .map_err(|e| format!("{e}, failed to fetch popular mods."))
+    query.app_id = BAROTRAUMA_GAME_ID;
+    // Clones share the connection pool, so concurrent pages do not wait on each other.
+    let mut client = STEAM_QUERY_CLIENT.read().await.clone();
+    client
+        .set_api_key(conf.steam_api_key)
+        .query(&query)
+        .map_err(|e| format!("{e}, failed to browse the workshop."))
         .await
 }
@@ -0,0 +1,249 @@
+//! Registry of SteamCMD download jobs.
+//!
+//! Every call to `download_mods` registers a job that stays `Queued` until it gets
+//! hold of the SteamCMD manager, then `Running` while its processes are alive.
+//! Jobs can be cancelled at any point through their [`CancellationToken`].
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use steamcmd_rs::CancellationToken;
+
+/// Finished jobs kept around for `list_jobs`; older ones are dropped first.
+const MAX_FINISHED_JOBS: usize = 50;
+
+/// Lifecycle state of a download job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+impl JobState {
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            JobState::Finished | JobState::Failed | JobState::Cancelled
+        )
+    }
+}
+
+/// Snapshot of a download job as reported to the frontend.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobInfo {
+    pub id: u64,
+    pub item_ids: Vec<u64>,
+    pub state: JobState,
+    /// PIDs of the SteamCMD processes spawned for this job.
+    pub pids: Vec<u32>,
+    /// Unix timestamps in seconds.
+    pub created_at: u64,
+    pub finished_at: Option<u64>,
+    pub error: Option<String>,
+}
+
+struct Job {
+    info: JobInfo,
+    cancel: CancellationToken,
+    /// Cancelled once the job reached a terminal state.
+    done: CancellationToken,
+}
+
+#[derive(Default)]
+struct Registry {
+    next_id: u64,
+    jobs: BTreeMap<u64, Job>,
+}
+
+/// Thread-safe registry of every download job started in this session.
+#[derive(Default)]
+pub struct JobRegistry(Mutex<Registry>);
+
+impl JobRegistry {
+    fn with<T>(&self, f: impl FnOnce(&mut Registry) -> T) -> T {
+        let mut registry = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut registry)
+    }
+
+    fn update(&self, id: u64, f: impl FnOnce(&mut Job)) -> Option<JobInfo> {
+        self.with(|registry| {
+            let job = registry.jobs.get_mut(&id)?;
+            f(job);
+            Some(job.info.clone())
+        })
+    }
+
+    /// Registers a new queued job and returns it with its cancellation token.
+    pub fn create(&self, item_ids: Vec<u64>) -> (JobInfo, CancellationToken) {
+        self.with(|registry| {
+            registry.next_id += 1;
+            let id = registry.next_id;
+            let info = JobInfo {
+                id,
+                item_ids,
+                state: JobState::Queued,
+                pids: Vec::new(),
+                created_at: now(),
+                finished_at: None,
+                error: None,
+            };
+            let cancel = CancellationToken::new();
+            registry.jobs.insert(
+                id,
+                Job {
+                    info: info.clone(),
+                    cancel: cancel.clone(),
+                    done: CancellationToken::new(),
+                },
+            );
+            (info, cancel)
+        })
+    }
+
+    /// Marks a queued job as running, unless it was cancelled in the meantime.
+    pub fn start(&self, id: u64) -> Option<JobInfo> {
+        self.update(id, |job| {
+            if job.info.state == JobState::Queued {
+                job.info.state = JobState::Running;
+            }
+        })
+    }
+
+    /// Records a SteamCMD process spawned for the job.
+    pub fn add_pid(&self, id: u64, pid: u32) {
+        self.update(id, |job| job.info.pids.push(pid));
+    }
+
+    /// Moves the job into a terminal state and prunes old finished jobs.
+    pub fn finish(&self, id: u64, error: Option<String>) -> Option<JobInfo> {
+        let info = self.update(id, |job| {
+            job.info.state = if job.cancel.is_cancelled() {
+                JobState::Cancelled
+            } else if error.is_some() {
+                JobState::Failed
+            } else {
+                JobState::Finished
+            };
+            job.info.error = error;
+            job.info.finished_at = Some(now());
+            job.done.cancel();
+        });
+        self.with(|registry| {
+            let finished: Vec<u64> = registry
+                .jobs
+                .iter()
+                .filter(|(_, job)| job.info.state.is_terminal())
+                .map(|(id, _)| *id)
+                .collect();
+            let excess = finished.len().saturating_sub(MAX_FINISHED_JOBS);
+            for id in &finished[..excess] {
+                registry.jobs.remove(id);
+            }
+        });
+        info
+    }
+
+    /// Cancels a job and waits up to `timeout` for its processes to be killed
+    /// and its partial downloads to be removed.
+    pub async fn cancel(&self, id: u64, timeout: Duration) -> Result<JobInfo, String> {
+        let done = self.with(|registry| {
+            let job = registry
+                .jobs
+                .get(&id)
+                .ok_or_else(|| format!("No download job with id {}", id))?;
+            if job.info.state.is_terminal() {
+                return Err(format!("Download job {} already {:?}", id, job.info.state));
+            }
+            job.cancel.cancel();
+            Ok(job.done.clone())
+        })?;
+        tokio::time::timeout(timeout, done.cancelled())
+            .await
+            .map_err(|_| format!("Download job {} did not stop in time", id))?;
+        self.list()
+            .into_iter()
+            .find(|info| info.id == id)
+            .ok_or_else(|| format!("No download job with id {}", id))
+    }
+
+    /// All known jobs, oldest first.
+    pub fn list(&self) -> Vec<JobInfo> {
+        self.with(|registry| registry.jobs.values().map(|job| job.info.clone()).collect())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_cancel_running_job() {
+        let registry = Arc::new(JobRegistry::default());
+        let (info, cancel) = registry.create(vec![1, 2]);
+        assert_eq!(registry.start(info.id).unwrap().state, JobState::Running);
+        registry.add_pid(info.id, 1234);
+
+        // The download task notices the cancellation and finishes the job
+        let worker = Arc::clone(&registry);
+        tokio::spawn(async move {
+            cancel.cancelled().await;
+            worker.finish(info.id, Some("Cancelled".to_string()));
+        });
+        let cancelled = registry
+            .cancel(info.id, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(cancelled.state, JobState::Cancelled);
+        assert_eq!(cancelled.pids, vec![1234]);
+        assert!(cancelled.finished_at.is_some());
+
+        assert!(
+            registry
+                .cancel(info.id, Duration::from_secs(5))
+                .await
+                .is_err()
+        );
+        assert!(registry.cancel(999, Duration::from_secs(5)).await.is_err());
+    }
+
+    #[test]
+    fn test_finish_prunes_oldest_finished_jobs() {
+        let registry = JobRegistry::default();
+        let (queued, _) = registry.create(vec![0]);
+        let (failed, _) = registry.create(vec![0]);
+        assert_eq!(
+            registry
+                .finish(failed.id, Some("Timeout".to_string()))
+                .unwrap()
+                .state,
+            JobState::Failed
+        );
+        let finished: Vec<u64> = (1..=MAX_FINISHED_JOBS as u64)
+            .map(|item_id| registry.create(vec![item_id]).0.id)
+            .collect();
+        for &id in &finished {
+            registry.finish(id, None);
+        }
+
+        // The queued job is kept, the oldest finished job made room for the newest
+        let jobs = registry.list();
+        assert_eq!(jobs.len(), MAX_FINISHED_JOBS + 1);
+        assert_eq!(jobs[0].id, queued.id);
+        assert_eq!(jobs[0].state, JobState::Queued);
+        assert!(jobs.iter().all(|job| job.id != failed.id));
+        assert!(jobs[1..].iter().all(|job| job.state == JobState::Finished));
+    }
+}
@@ -1,4 +1,5 @@
 mod commands;
+mod jobs;
 mod once;
 
 use configuration::{Config, Level};
@@ -23,17 +24,30 @@ pub fn run() -> Result<(), String> {
             write_config,
             list_installed_mods,
             download_mods,
+            cancel_download,
+            list_jobs,
+            install_steamcmd,
+            verify_mods,
+            list_workshop_cache,
+            prune_workshop_cache,
             list_mod_lists,
             get_background_image,
+            get_preview_thumbnail,
+            clear_preview_cache,
             get_default_config,
             get_build_info,
             list_enabled_mods,
             retrieve_mod_metadata,
+            list_mods_by_creator,
             is_barotrauma_mod,
+            validate_workshop_items,
             install_mods,
             uninstall_mods,
             get_mod_occupation,
             get_mod_hash,
+            get_mod_content_files,
+            validate_mod,
+            verify_expected_hashes,
             get_workshop_items,
             check_mod_updates,
             create_mod_list,
@@ -46,10 +60,12 @@ pub fn run() -> Result<(), String> {
             compare_profiles,
             export_profile,
             import_profile,
+            import_collection,
             detect_mod_conflicts,
             check_workshop_updates,
+            get_mod_change_notes,
             check_network_status,
-            get_popular_mods
+            browse_workshop
         ])
         .run(tauri::generate_context!())
         .map_err(|e| e.to_string())?;
@@ -1,6 +1,16 @@
+use crate::commands::{
+    metadata_cache_policy, preview_cache_max_bytes, read_config, workshop_request_policy,
+};
+use crate::jobs::JobRegistry;
+use configuration::Config;
+use constants::{PREVIEW_CACHE_DIR, WORKSHOP_METADATA_CACHE_FILE};
+use imagen::PreviewCache;
 use mod_analyzer::BarotraumaModManager;
-use std::sync::LazyLock;
-use steam_api::SteamWorkShopClient;
+use std::sync::{Arc, LazyLock};
+use steam_api::{
+    MetadataCache, PlayerSummariesClient, QueryFilesClient, SharedRequestPolicy,
+    SteamWorkShopClient,
+};
 use steamcmd_rs::SteamCMD;
 use tokio::sync::RwLock;
 /// A static instance of BarotraumaModManager
@@ -11,5 +21,51 @@ pub static BARO_MANAGER: LazyLock<RwLock<BarotraumaModManager>> =
 pub static STEAMCMD_MANAGER: LazyLock<RwLock<SteamCMD>> =
     LazyLock::new(|| SteamCMD::default().into());
 
-pub static STEAM_WORKSHOP_CLIENT: LazyLock<RwLock<SteamWorkShopClient>> =
-    LazyLock::new(|| SteamWorkShopClient::new().into());
+/// On-disk cache of workshop metadata, its policy follows the config
+pub static WORKSHOP_METADATA_CACHE: LazyLock<Arc<MetadataCache>> = LazyLock::new(|| {
+    let policy = read_config()
+        .map(|conf| metadata_cache_policy(&conf))
+        .unwrap_or_default();
+    MetadataCache::open(WORKSHOP_METADATA_CACHE_FILE.clone(), policy).into()
+});
+
+/// Steam Web API request policy of every Steam client, it follows the config
+pub static STEAM_REQUEST_POLICY: LazyLock<SharedRequestPolicy> = LazyLock::new(|| {
+    read_config()
+        .map(|conf| workshop_request_policy(&conf))
+        .unwrap_or_default()
+        .into()
+});
+
+/// Workshop metadata client
+pub static STEAM_WORKSHOP_CLIENT: LazyLock<RwLock<SteamWorkShopClient>> = LazyLock::new(|| {
+    SteamWorkShopClient::new()
+        .with_cache(WORKSHOP_METADATA_CACHE.clone())
+        .with_request_policy(STEAM_REQUEST_POLICY.clone())
+        .into()
+});
+
+/// Workshop browse/search client, the API key is taken from the config on every query
+pub static STEAM_QUERY_CLIENT: LazyLock<RwLock<QueryFilesClient>> = LazyLock::new(|| {
+    QueryFilesClient::new()
+        .with_request_policy(STEAM_REQUEST_POLICY.clone())
+        .into()
+});
+
+/// Creator profile client, the API key is taken from the config on every lookup
+pub static STEAM_PLAYER_CLIENT: LazyLock<RwLock<PlayerSummariesClient>> = LazyLock::new(|| {
+    PlayerSummariesClient::new()
+        .with_request_policy(STEAM_REQUEST_POLICY.clone())
+        .into()
+});
+
+/// Cached preview thumbnails, the size limit follows the config
+pub static PREVIEW_CACHE: LazyLock<PreviewCache> = LazyLock::new(|| {
+    let max_bytes = read_config()
+        .map(|conf| preview_cache_max_bytes(&conf))
+        .unwrap_or_else(|_| preview_cache_max_bytes(&Config::default()));
+    PreviewCache::new(PREVIEW_CACHE_DIR.clone(), max_bytes)
+});
+
+/// Download jobs started by `download_mods`
+pub static DOWNLOAD_JOBS: LazyLock<JobRegistry> = LazyLock::new(JobRegistry::default);
@@ -0,0 +1,36 @@
+import { type MaybeRefOrGetter, type Ref, ref, toValue, watchEffect } from "vue";
+import { get_preview_thumbnail } from "../invokes.ts";
+
+/**
+ * Thumbnail of a workshop preview served from the local preview cache
+ * @param hash - The mod's `previewHash`
+ * @param url - The preview URL, used as is when the thumbnail cannot be loaded
+ * @param size - Wanted edge length in pixels
+ */
+export function usePreview(
+	hash: MaybeRefOrGetter<string | undefined>,
+	url: MaybeRefOrGetter<string | undefined>,
+	size: number,
+): Ref<string | undefined> {
+	const src = ref<string | undefined>();
+	watchEffect(async (onCleanup) => {
+		let stale = false;
+		onCleanup(() => {
+			stale = true;
+		});
+		const previewHash = toValue(hash);
+		const previewUrl = toValue(url);
+		let resolved = previewUrl;
+		if (previewHash && previewUrl) {
+			try {
+				resolved = await get_preview_thumbnail(previewHash, previewUrl, size);
+			} catch (error) {
+				console.warn("Preview thumbnail unavailable", error);
+			}
+		}
+		if (!stale) {
+			src.value = resolved;
+		}
+	});
+	return src;
+}
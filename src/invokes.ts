@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { computed, type Ref, ref } from "vue";
 import { BuildInfo } from "./proto/build_info.ts";
 import { Config } from "./proto/config";
@@ -35,8 +36,136 @@ export async function list_installed_mods() {
 	installed_mod.value = await invoke("list_installed_mods");
 }
 
-export async function download_mods(mods: number[]) {
-	await invoke("download_mods", { mods });
+/** Outcome of downloading a single workshop item. */
+/** One attempt at downloading an item, `attempt` 1 is the initial download. */
+export interface Attempt {
+	attempt: number;
+	success: boolean;
+	reason: string | null;
+	bytes: number | null;
+}
+
+export interface ItemResult {
+	itemId: number;
+	success: boolean;
+	reason: string | null;
+	path: string | null;
+	bytes: number | null;
+	attempts: Attempt[];
+}
+
+/** Per-item report returned by `download_mods`. */
+export interface DownloadReport {
+	items: ItemResult[];
+}
+
+export async function download_mods(mods: number[]): Promise<DownloadReport> {
+	return await invoke("download_mods", { mods });
+}
+
+/** Per-item progress parsed from SteamCMD output during `download_mods`. */
+export type DownloadProgressEvent =
+	| { type: "downloadStarted"; itemId: number }
+	| { type: "downloadSucceeded"; itemId: number; path: string; bytes: number }
+	| { type: "downloadFailed"; itemId: number; reason: string };
+
+/** Subscribes to download progress events emitted by `download_mods`. */
+export async function on_download_progress(
+	handler: (event: DownloadProgressEvent) => void,
+): Promise<UnlistenFn> {
+	return await listen<DownloadProgressEvent>("download-progress", (e) =>
+		handler(e.payload),
+	);
+}
+
+export type Integrity = "intact" | "repaired" | "corrupted" | "unverified";
+
+export interface ItemIntegrity {
+	itemId: number;
+	status: Integrity;
+	expectedHash: string | null;
+	hashBefore: string | null;
+	hashAfter: string | null;
+	reason: string | null;
+}
+
+/** Per-item report returned by `verify_mods`. */
+export interface VerifyReport {
+	items: ItemIntegrity[];
+}
+
+/** Validates mods with SteamCMD and repairs the ones whose hash no longer matches. */
+export async function verify_mods(modIds: number[]): Promise<VerifyReport> {
+	return await invoke("verify_mods", { modIds });
+}
+
+export type CacheState = "copied" | "linked" | "orphaned";
+
+/** One item in SteamCMD's workshop content folder. */
+export interface CacheEntry {
+	itemId: number;
+	path: string;
+	bytes: number;
+	state: CacheState;
+}
+
+export interface PruneOptions {
+	orphaned: boolean;
+	copied: boolean;
+	dryRun: boolean;
+}
+
+export interface PruneReport {
+	removed: CacheEntry[];
+	bytesReclaimed: number;
+	dryRun: boolean;
+	/** `[itemId, reason]` for entries that could not be removed. */
+	errors: [number, string][];
+}
+
+export async function list_workshop_cache(): Promise<CacheEntry[]> {
+	return await invoke("list_workshop_cache");
+}
+
+/** Removes orphaned and/or copied cache entries; `dryRun` only reports them. */
+export async function prune_workshop_cache(
+	options: PruneOptions,
+): Promise<PruneReport> {
+	return await invoke("prune_workshop_cache", { options });
+}
+
+/** Installs SteamCMD into the app's own directory, returning its new home. */
+export async function install_steamcmd(url?: string): Promise<string> {
+	return await invoke("install_steamcmd", { url: url ?? null });
+}
+
+export type JobState = "queued" | "running" | "finished" | "failed" | "cancelled";
+
+/** A download job started by `download_mods`. */
+export interface JobInfo {
+	id: number;
+	itemIds: number[];
+	state: JobState;
+	pids: number[];
+	createdAt: number;
+	finishedAt: number | null;
+	error: string | null;
+}
+
+/** Kills a queued or running download job and removes its partial downloads. */
+export async function cancel_download(jobId: number): Promise<JobInfo> {
+	return await invoke("cancel_download", { jobId });
+}
+
+export async function list_jobs(): Promise<JobInfo[]> {
+	return await invoke("list_jobs");
+}
+
+/** Subscribes to download job state changes. */
+export async function on_download_job(
+	handler: (job: JobInfo) => void,
+): Promise<UnlistenFn> {
+	return await listen<JobInfo>("download-job", (e) => handler(e.payload));
 }
 
 export async function list_mod_lists() {
@@ -82,6 +211,13 @@ export async function retrieve_mod_metadata() {
 	});
 }
 
+/** Lists the installed mods of one creator, `creator` is `CreatorProfile.steamId`. */
+export async function list_mods_by_creator(
+	creator: string,
+): Promise<BarotraumaMod[]> {
+	return await invoke("list_mods_by_creator", { creator });
+}
+
 export async function is_barotrauma_mod(
 	item: number | WorkshopItem,
 ): Promise<boolean> {
@@ -91,6 +227,34 @@ export async function is_barotrauma_mod(
 	return item.consumerAppId === BAROTRAUMA_GAME_ID;
 }
 
+export type Availability =
+	| { kind: "public" }
+	| { kind: "friendsOnly" }
+	| { kind: "private" }
+	| { kind: "unlisted" }
+	| { kind: "banned"; reason: string };
+
+/** Why a workshop item should not be downloaded. */
+export type ValidationIssue =
+	| { kind: "notFound"; result: number }
+	| { kind: "wrongApp"; consumerAppId: number }
+	| { kind: "banned"; reason: string }
+	| { kind: "hidden"; availability: Availability };
+
+export interface ItemValidation {
+	itemId: number;
+	item?: WorkshopItem;
+	/** Empty when the item can be downloaded. */
+	issues: ValidationIssue[];
+}
+
+/** Validates many workshop items with batched lookups before they are downloaded. */
+export async function validate_workshop_items(
+	itemIds: number[],
+): Promise<ItemValidation[]> {
+	return await invoke("validate_workshop_items", { itemIds });
+}
+
 export async function install_mods(modIds: number[]): Promise<null> {
 	return await invoke("install_mods", { modIds });
 }
@@ -99,6 +263,19 @@ export async function get_background_image(): Promise<string | null> {
 	return await invoke("get_background_image");
 }
 
+/** Cached thumbnail of a workshop preview as a data URL, downloaded on first use. */
+export async function get_preview_thumbnail(
+	hash: string,
+	url: string,
+	size: number,
+): Promise<string> {
+	return await invoke("get_preview_thumbnail", { hash, url, size });
+}
+
+export async function clear_preview_cache(): Promise<null> {
+	return await invoke("clear_preview_cache");
+}
+
 export async function get_mod_occupation(modId: number): Promise<number> {
 	return await invoke("get_mod_occupation", { modId });
 }
@@ -107,6 +284,74 @@ export async function get_mod_hash(modId: number): Promise<string> {
 	return await invoke("get_mod_hash", { modId });
 }
 
+/** A file referenced from a mod's filelist.xml. */
+export interface ContentFile {
+	/** The content type, the element name such as "Item" or "Character". */
+	elementType: string;
+	/** The `file` attribute as written in filelist.xml. */
+	file: string;
+	/** The resolved path, null when it refers to a mod that is not installed. */
+	path: string | null;
+	exists: boolean;
+}
+
+/** What is wrong with a file referenced from filelist.xml. */
+export type FileIssue =
+	| { kind: "missing" }
+	| { kind: "caseMismatch"; actual: string }
+	| { kind: "unresolved" }
+	| { kind: "malformedXml"; message: string; line: number; column: number };
+
+/** An issue found in one file referenced from filelist.xml. */
+export interface FileDiagnostic {
+	elementType: string;
+	file: string;
+	path: string | null;
+	issue: FileIssue;
+}
+
+/** Result of validating a mod's referenced files. */
+export interface ModValidation {
+	modSteamId: number;
+	filesChecked: number;
+	diagnostics: FileDiagnostic[];
+	/** Percentage of referenced files without issues. */
+	healthScore: number;
+}
+
+/** Outcome of comparing a mod with its declared `expectedhash`. */
+export type HashStatus =
+	| { kind: "match" }
+	| { kind: "mismatch" }
+	| { kind: "notDeclared" }
+	| { kind: "error"; message: string };
+
+/** Result of verifying one installed mod's `expectedhash`. */
+export interface HashCheck {
+	modName: string;
+	modSteamId: number;
+	expectedHash: string;
+	actualHash: string | null;
+	status: HashStatus;
+}
+
+/** Compares every installed mod with the `expectedhash` of its filelist.xml. */
+export async function verify_expected_hashes(): Promise<HashCheck[]> {
+	return await invoke("verify_expected_hashes");
+}
+
+/** Checks the files an installed mod references and records its health score. */
+export async function validate_mod(modId: number): Promise<ModValidation> {
+	return await invoke("validate_mod", { modId });
+}
+
+/** Lists the files an installed mod's filelist.xml references. */
+export async function get_mod_content_files(
+	modId: number,
+): Promise<ContentFile[]> {
+	return await invoke("get_mod_content_files", { modId });
+}
+
 /** Status for a single mod in an update check. */
 export interface ModUpdateStatus {
 	modId: number;
@@ -143,11 +388,24 @@ export async function delete_mod_list(profileName: string): Promise<void> {
 	await refresh_config();
 }
 
-export async function apply_mod_list(profileName: string): Promise<void> {
-	await invoke("apply_mod_list", { profileName });
+/** Result of applying a profile. */
+export interface AppliedProfile {
+	/** The installed game's version, null when it could not be detected. */
+	gameVersion: string | null;
+	/** Enabled mods built for an older or newer game version. */
+	incompatibleMods: BarotraumaMod[];
+}
+
+export async function apply_mod_list(
+	profileName: string,
+): Promise<AppliedProfile> {
+	const applied: AppliedProfile = await invoke("apply_mod_list", {
+		profileName,
+	});
 	await list_installed_mods();
 	await list_enabled_mods();
 	await refresh_config();
+	return applied;
 }
 
 export async function set_active_profile(name: string): Promise<void> {
@@ -206,17 +464,56 @@ export async function import_profile(path: string): Promise<ModList> {
 	return result;
 }
 
+/**
+ * Turns a Steam Workshop collection (ID or URL) into a profile, downloading and
+ * installing the mods of the collection that are missing.
+ */
+export async function import_collection(
+	collection: string,
+	profileName?: string,
+): Promise<ModList> {
+	const result = (await invoke("import_collection", {
+		collection,
+		profileName,
+	})) as ModList;
+	await list_mod_lists();
+	return result;
+}
+
+/** Where a dependency was declared: content.xml, Workshop "Required Items" or both. */
+export type DependencySource = "xml" | "workshop" | "both";
+
 /** A dependency declared by a mod that is not satisfied by any enabled mod. */
 export interface MissingDependency {
 	modName: string;
 	modSteamId: number;
 	dependencyName: string;
 	dependencySteamId: number | null;
+	source: DependencySource;
+}
+
+/** One mod's definition of a prefab that several enabled mods define. */
+export interface ConflictingDefinition {
+	modName: string;
+	modSteamId: number;
+	file: string;
+	isOverride: boolean;
+}
+
+/** A prefab identifier defined by more than one enabled mod. */
+export interface ContentConflict {
+	contentType: string;
+	identifier: string;
+	/** "duplicate" when several mods define it without an `<Override>`. */
+	kind: "override" | "duplicate";
+	/** In load order, the first definition is the one the game uses. */
+	definitions: ConflictingDefinition[];
 }
 
 /** Result of conflict detection among enabled mods. */
 export interface ConflictReport {
 	missingDependencies: MissingDependency[];
+	contentConflicts: ContentConflict[];
 }
 
 /** Detects missing dependencies among currently enabled mods. */
@@ -231,11 +528,14 @@ export interface WorkshopUpdateStatus {
 	hasUpdate: boolean;
 	localLastModified: number | null;
 	workshopLastUpdated: number | null;
+	/** Where the workshop timestamp came from. */
+	workshopSource: "network" | "cache" | "stale" | null;
 }
 
 /** Network connectivity status. */
 export interface NetworkStatus {
 	steamApi: boolean;
+	steamApiRateLimited: boolean;
 	steamcmdAvailable: boolean;
 }
 
@@ -246,12 +546,45 @@ export async function check_workshop_updates(): Promise<
 	return await invoke("check_workshop_updates");
 }
 
+/** One entry of a workshop item's change notes. */
+export interface ChangeNote {
+	/** Unix timestamp of the update. */
+	timestamp: number;
+	text: string;
+}
+
+/** Fetches a mod's workshop change notes newer than the installed version, newest first. */
+export async function get_mod_change_notes(modId: number): Promise<ChangeNote[]> {
+	return await invoke("get_mod_change_notes", { modId });
+}
+
 /** Checks network connectivity to Steam services. */
 export async function check_network_status(): Promise<NetworkStatus> {
 	return await invoke("check_network_status");
 }
 
-/** Returns popular Barotrauma mods from the Steam Workshop. */
-export async function get_popular_mods(): Promise<WorkshopItem[]> {
-	return await invoke("get_popular_mods");
+export type Ranking = "trend" | "mostSubscribed" | "mostRecent" | "textSearch";
+
+export interface WorkshopQuery {
+	ranking: Ranking;
+	/** Only mods carrying every one of these tags. */
+	tags?: string[];
+	searchText?: string;
+	/** Time window of the `trend` ranking in days. */
+	days?: number;
+	/** `nextCursor` of the previous page, omitted for the first page. */
+	cursor?: string;
+	perPage?: number;
+}
+
+export interface QueryPage {
+	total: number;
+	items: WorkshopItem[];
+	/** Cursor of the following page, absent on the last page. */
+	nextCursor?: string | null;
+}
+
+/** Browses or searches Barotrauma mods on the Steam Workshop, one page at a time. */
+export async function browse_workshop(query: WorkshopQuery): Promise<QueryPage> {
+	return await invoke("browse_workshop", { query });
 }